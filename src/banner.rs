@@ -0,0 +1,546 @@
+//! Banners render text in large letters from FIGlet `.flf` fonts. A font file starts with a
+//! header line such as `flf2a$ 6 5 20 15 3 0 143`, giving the hardblank character, the glyph
+//! height, the baseline, the widest line, the old layout, the number of comment lines and
+//! optionally the print direction and full layout. The comment lines follow, then the glyphs of
+//! ASCII 32 to 126, seven optional German glyphs and any glyphs tagged with their code, each a
+//! run of lines ended with an endmark such as `@`.
+
+use crate::canvas::OmmaRegion;
+use crate::cell::{DEFAULT_CELL, OmmaCell};
+use crate::color::{OmmaColor, OmmaGradient};
+use crate::error::OmmaErr;
+use crate::theme::OmmaRole;
+use crate::window::Window;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+/// The built in font, full width block letters five rows tall
+const BLOCK_FONT: &str = include_str!("../fonts/block.flf");
+
+/// The German glyphs that follow ASCII in every font
+const DEUTSCH: [char; 7] = ['Ä', 'Ö', 'Ü', 'ä', 'ö', 'ü', 'ß'];
+
+/// Horizontal smushing rules, kerning and smushing flags as FIGlet's full layout numbers them
+const SMUSH_EQUAL: u32 = 1;
+const SMUSH_LOWLINE: u32 = 2;
+const SMUSH_HIERARCHY: u32 = 4;
+const SMUSH_PAIR: u32 = 8;
+const SMUSH_BIGX: u32 = 16;
+const SMUSH_HARDBLANK: u32 = 32;
+const SMUSH_RULES: u32 = 63;
+const LAYOUT_KERN: u32 = 64;
+const LAYOUT_SMUSH: u32 = 128;
+
+/// FontLayout picks how close together a font's glyphs are set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontLayout {
+    /// Each glyph keeps its full width
+    FullWidth,
+    /// Glyphs move together until they touch
+    Kerning,
+    /// Glyphs move one column further and overlap where the font's smushing rules allow
+    Smushing,
+}
+
+/// OmmaFont is a FIGlet font
+#[derive(Clone)]
+pub struct OmmaFont {
+    height: usize,
+    hardblank: char,
+    layout: u32,
+    glyphs: HashMap<char, Vec<Vec<char>>>,
+}
+
+impl OmmaFont {
+    /// default_font returns the built in block font
+    pub fn default_font() -> &'static OmmaFont {
+        static FONT: OnceLock<OmmaFont> = OnceLock::new();
+        FONT.get_or_init(|| Self::parse(BLOCK_FONT).expect("the built in font is valid"))
+    }
+
+    /// parse reads a font from the text of a .flf file, see the module documentation
+    pub fn parse(text: &str) -> Result<Self, OmmaErr> {
+        let mut lines = text.lines().enumerate();
+        let invalid = |line: usize, reason: &str| {
+            Err(OmmaErr::new(&format!("font line {}: {}", line + 1, reason)))
+        };
+
+        let Some((_, header)) = lines.next() else {
+            return invalid(0, "empty font");
+        };
+        let Some(signature) = header.strip_prefix("flf2a") else {
+            return invalid(0, "missing flf2a signature");
+        };
+        let mut chars = signature.chars();
+        let Some(hardblank) = chars.next() else {
+            return invalid(0, "missing hardblank");
+        };
+        let fields: Vec<i64> = match chars
+            .as_str()
+            .split_whitespace()
+            .map(|field| field.parse())
+            .collect()
+        {
+            Ok(fields) => fields,
+            Err(_) => return invalid(0, "header fields must be numbers"),
+        };
+        let (height, old_layout, comments) = match fields[..] {
+            [height, _, _, old_layout, comments, ..] if height > 0 && comments >= 0 => {
+                (height as usize, old_layout, comments as usize)
+            }
+            _ => {
+                return invalid(
+                    0,
+                    "expected height, baseline, max length, layout and comments",
+                );
+            }
+        };
+        let layout = match fields.get(6) {
+            Some(full_layout) => *full_layout as u32 & (SMUSH_RULES | LAYOUT_KERN | LAYOUT_SMUSH),
+            None if old_layout < 0 => 0,
+            None if old_layout == 0 => LAYOUT_KERN,
+            None => old_layout as u32 & SMUSH_RULES | LAYOUT_SMUSH,
+        };
+        // Sizes come from the file, so check them against its length before anything is read or
+        // allocated by them
+        if comments.saturating_add(height) > lines.clone().count() {
+            return invalid(0, "comments and glyph height run past the end of the font");
+        }
+        for _ in 0..comments {
+            lines.next();
+        }
+
+        let mut font = OmmaFont {
+            height,
+            hardblank,
+            layout,
+            glyphs: HashMap::new(),
+        };
+        let read_glyph = |lines: &mut dyn Iterator<Item = (usize, &str)>| {
+            let mut rows = Vec::with_capacity(height);
+            for _ in 0..height {
+                let (_, line) = lines.next()?;
+                // Every line ends in one or two endmarks, the last character before whitespace
+                let line = line.trim_end();
+                let endmark = line.chars().last();
+                let row: Vec<char> = line
+                    .trim_end_matches(|c| Some(c) == endmark)
+                    .chars()
+                    .collect();
+                rows.push(row);
+            }
+            Some(rows)
+        };
+
+        // Required glyphs come in order, fonts that stop early just lack the rest
+        let required = (32..127u8).map(char::from).chain(DEUTSCH);
+        for ch in required {
+            let Some(rows) = read_glyph(&mut lines) else {
+                return Ok(font);
+            };
+            font.insert(ch, rows);
+        }
+        while let Some((number, tag)) = lines.next() {
+            if tag.trim().is_empty() {
+                continue;
+            }
+            let code = tag.split_whitespace().next().unwrap_or_default();
+            let parsed = if let Some(hex) = code.strip_prefix("0x").or(code.strip_prefix("0X")) {
+                i64::from_str_radix(hex, 16)
+            } else if code.len() > 1
+                && let Some(octal) = code.strip_prefix('0')
+            {
+                i64::from_str_radix(octal, 8)
+            } else {
+                code.parse()
+            };
+            let Ok(code) = parsed else {
+                return invalid(number, &format!("invalid character code \"{}\"", code));
+            };
+            let Some(rows) = read_glyph(&mut lines) else {
+                return invalid(number, "glyph is cut short");
+            };
+            // Negative codes are reserved for translation tables and never printed
+            if let Some(ch) = u32::try_from(code).ok().and_then(char::from_u32) {
+                font.insert(ch, rows);
+            }
+        }
+        Ok(font)
+    }
+
+    /// load reads a .flf font file, see parse
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, OmmaErr> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// insert pads the rows of a glyph to the same width and adds it to the font
+    fn insert(&mut self, ch: char, rows: Vec<Vec<char>>) {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let rows = rows
+            .into_iter()
+            .map(|mut row| {
+                row.resize(width, ' ');
+                row
+            })
+            .collect();
+        self.glyphs.insert(ch, rows);
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// layout returns how the font sets its glyphs
+    pub fn layout(&self) -> FontLayout {
+        if self.layout & LAYOUT_SMUSH != 0 {
+            FontLayout::Smushing
+        } else if self.layout & LAYOUT_KERN != 0 {
+            FontLayout::Kerning
+        } else {
+            FontLayout::FullWidth
+        }
+    }
+
+    /// set_layout overrides the layout the font was made for, keeping its smushing rules
+    pub fn set_layout(&mut self, layout: FontLayout) {
+        self.layout &= SMUSH_RULES;
+        self.layout |= match layout {
+            FontLayout::FullWidth => 0,
+            FontLayout::Kerning => LAYOUT_KERN,
+            FontLayout::Smushing => LAYOUT_SMUSH,
+        };
+    }
+
+    pub fn has_glyph(&self, ch: char) -> bool {
+        self.glyphs.contains_key(&ch)
+    }
+
+    /// smush returns the character left and right overlap into, or None if they can not
+    fn smush(
+        &self,
+        left: char,
+        right: char,
+        left_width: usize,
+        right_width: usize,
+    ) -> Option<char> {
+        if left == ' ' {
+            return Some(right);
+        }
+        if right == ' ' {
+            return Some(left);
+        }
+        // Glyphs narrower than two columns would vanish into their neighbours
+        if left_width < 2 || right_width < 2 || self.layout & LAYOUT_SMUSH == 0 {
+            return None;
+        }
+        let hardblank = self.hardblank;
+        let rules = self.layout & SMUSH_RULES;
+        if rules == 0 {
+            // Universal smushing, the right glyph wins over everything but hardblanks
+            return Some(if right == hardblank { left } else { right });
+        }
+        if rules & SMUSH_HARDBLANK != 0 && left == hardblank && right == hardblank {
+            return Some(left);
+        }
+        if left == hardblank || right == hardblank {
+            return None;
+        }
+        if rules & SMUSH_EQUAL != 0 && left == right {
+            return Some(left);
+        }
+        if rules & SMUSH_LOWLINE != 0 {
+            const LOWLINE: &str = "|/\\[]{}()<>";
+            if left == '_' && LOWLINE.contains(right) {
+                return Some(right);
+            }
+            if right == '_' && LOWLINE.contains(left) {
+                return Some(left);
+            }
+        }
+        if rules & SMUSH_HIERARCHY != 0 {
+            const CLASSES: [&str; 6] = ["|", "/\\", "[]", "{}", "()", "<>"];
+            let class = |ch: char| CLASSES.iter().position(|class| class.contains(ch));
+            if let (Some(left_class), Some(right_class)) = (class(left), class(right))
+                && left_class != right_class
+            {
+                return Some(if left_class > right_class {
+                    left
+                } else {
+                    right
+                });
+            }
+        }
+        if rules & SMUSH_PAIR != 0
+            && matches!(
+                (left, right),
+                ('[', ']') | (']', '[') | ('{', '}') | ('}', '{') | ('(', ')') | (')', '(')
+            )
+        {
+            return Some('|');
+        }
+        if rules & SMUSH_BIGX != 0 {
+            match (left, right) {
+                ('/', '\\') => return Some('|'),
+                ('\\', '/') => return Some('Y'),
+                ('>', '<') => return Some('X'),
+                _ => (),
+            }
+        }
+        None
+    }
+
+    /// overlap returns how many columns glyph can move into the rows laid out so far
+    fn overlap(
+        &self,
+        rows: &[Vec<(char, usize)>],
+        glyph: &[Vec<char>],
+        left_width: usize,
+    ) -> usize {
+        if self.layout & (LAYOUT_KERN | LAYOUT_SMUSH) == 0 {
+            return 0;
+        }
+        let width = glyph.first().map_or(0, |row| row.len());
+        let mut overlap = width;
+        for (row, glyph_row) in rows.iter().zip(glyph) {
+            let right = glyph_row.iter().position(|ch| *ch != ' ').unwrap_or(width);
+            let left = row.iter().rposition(|(ch, _)| *ch != ' ');
+            // Blank columns on both sides close up, plus one more where the edges smush
+            let mut amount = right + row.len() - left.map_or(0, |left| left + 1);
+            if let (Some(left), Some(ch)) = (left, glyph_row.get(right))
+                && self.smush(row[left].0, *ch, left_width, width).is_some()
+            {
+                amount += 1;
+            }
+            overlap = overlap.min(amount);
+        }
+        overlap
+    }
+
+    /// layout_line sets text in the font as rows of characters, each tagged with the position in
+    /// text of the glyph it came from counting on from first
+    fn layout_line(&self, text: &str, first: usize) -> Vec<Vec<(char, usize)>> {
+        let mut rows = vec![Vec::new(); self.height];
+        let mut left_width = 0;
+        for (index, ch) in text.chars().enumerate() {
+            let Some(glyph) = self.glyphs.get(&ch) else {
+                continue;
+            };
+            let width = glyph.first().map_or(0, |row| row.len());
+            let overlap = self.overlap(&rows, glyph, left_width);
+            for (row, glyph_row) in rows.iter_mut().zip(glyph) {
+                let start = row.len() as isize - overlap as isize;
+                for (column, ch) in glyph_row.iter().enumerate() {
+                    let at = start + column as isize;
+                    if at < 0 {
+                        continue;
+                    }
+                    let tagged = (*ch, first + index);
+                    match row.get_mut(at as usize) {
+                        Some(under) => {
+                            let smushed = self.smush(under.0, *ch, left_width, width);
+                            let smushed = smushed.unwrap_or(*ch);
+                            if smushed != under.0 || under.0 == ' ' {
+                                *under = (smushed, first + index);
+                            }
+                        }
+                        None => row.push(tagged),
+                    }
+                }
+            }
+            left_width = width;
+        }
+        rows
+    }
+}
+
+/// Banner draws text in large letters from a FIGlet font
+#[derive(Clone)]
+pub struct Banner {
+    text: String,
+    font: Option<Rc<OmmaFont>>,
+    cell: OmmaCell,
+    glyph_colors: Vec<OmmaColor>,
+    gradient: OmmaGradient,
+}
+
+impl Banner {
+    /// new creates a banner in the built in font drawn in the title role. Lines of text are
+    /// stacked below each other.
+    pub fn new(text: &str) -> Self {
+        Banner {
+            text: text.to_string(),
+            font: None,
+            cell: OmmaCell {
+                role: Some(OmmaRole::Title),
+                ..DEFAULT_CELL
+            },
+            glyph_colors: Vec::new(),
+            gradient: OmmaGradient::new(),
+        }
+    }
+
+    pub fn font(mut self, font: &OmmaFont) -> Self {
+        self.font = Some(Rc::new(font.clone()));
+        self
+    }
+
+    /// face returns the banner's font or the built in one
+    fn face(&self) -> &OmmaFont {
+        self.font.as_deref().unwrap_or(OmmaFont::default_font())
+    }
+
+    /// cell sets the style the letters are drawn in, their glyphs come from the font
+    pub fn cell(mut self, cell: &OmmaCell) -> Self {
+        self.cell = cell.clone();
+        self
+    }
+
+    /// glyph_colors colors each letter in turn with the next color, starting over when they run
+    /// out. It replaces the cell's fg.
+    pub fn glyph_colors(mut self, colors: &[OmmaColor]) -> Self {
+        self.glyph_colors = colors.to_vec();
+        self
+    }
+
+    /// gradient colors the letters by their column across the banner, interpolating between
+    /// evenly spaced stops. It replaces the cell's fg and the glyph colors.
+    pub fn gradient(mut self, stops: &[OmmaColor]) -> Self {
+        self.gradient = OmmaGradient::even(stops);
+        self
+    }
+
+    /// gradient_raw colors the letters by sampling gradient at their column across the banner
+    pub fn gradient_raw(mut self, gradient: &OmmaGradient) -> Self {
+        self.gradient = gradient.clone();
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+    }
+
+    /// layout sets every line of text in the font, padded to the same width
+    fn layout(&self) -> Vec<Vec<(char, usize)>> {
+        let mut rows = Vec::new();
+        let mut first = 0;
+        for line in self.text.split('\n') {
+            rows.extend(self.face().layout_line(line, first));
+            first += line.chars().count();
+        }
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        for row in rows.iter_mut() {
+            row.resize(width, (' ', 0));
+        }
+        rows
+    }
+
+    pub fn width(&self) -> usize {
+        self.layout().first().map_or(0, |row| row.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.face().height() * self.text.split('\n').count()
+    }
+
+    /// rows returns the banner as plain text, hardblanks shown as spaces
+    pub fn rows(&self) -> Vec<String> {
+        let hardblank = self.face().hardblank;
+        self.layout()
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|(ch, _)| if *ch == hardblank { ' ' } else { *ch })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// region returns the banner as cells. Spaces are transparent, hardblanks are drawn as blank
+    /// cells in the banner's style.
+    pub fn region(&self) -> OmmaRegion {
+        let rows = self.layout();
+        let width = rows.first().map_or(0, |row| row.len());
+        let hardblank = self.face().hardblank;
+        let mut region = OmmaRegion::new(width, rows.len(), &OmmaCell::transparent());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, (ch, index)) in row.iter().enumerate() {
+                if *ch == ' ' {
+                    continue;
+                }
+                let mut cell = OmmaCell {
+                    ch: if *ch == hardblank { ' ' } else { *ch },
+                    ..self.cell.clone()
+                };
+                let position = x as f64 / width.saturating_sub(1).max(1) as f64;
+                let color = self.gradient.sample(position).or(self
+                    .glyph_colors
+                    .get(index % self.glyph_colors.len().max(1))
+                    .copied());
+                if let Some(color) = color {
+                    // Explicit colors win over the theme
+                    cell.fg = Some(color);
+                    cell.role = None;
+                }
+                let _ = region.set(x, y, &cell);
+            }
+        }
+        region
+    }
+
+    /// draw writes the banner into window with its top left corner at x, y, clipped to the
+    /// window's content buffer. The cells behind spaces are left alone.
+    pub fn draw(&self, window: &mut Window, x: usize, y: usize) -> Result<u32, OmmaErr> {
+        if x >= window.width() || y >= window.height() {
+            return Err(OmmaErr::new(&format!(
+                "window_id {} invalid banner draw at {}:{} (max {}:{})",
+                window.id(),
+                x,
+                y,
+                window.width().saturating_sub(1),
+                window.height().saturating_sub(1)
+            )));
+        }
+        let region = self.region();
+        Ok(window.paste_region(
+            x as isize,
+            y as isize,
+            &region,
+            Some(&OmmaCell::transparent()),
+        ))
+    }
+}
+
+#[test]
+fn smushing() {
+    // Two glyph font: a slash pair under every rule, then kerning alone
+    let font = "flf2a$ 2 2 4 -1 0 0 191\n".to_string()
+        + &" @\n @@\n".repeat(15)
+        + "/\\@\n\\/@@\n"
+        + &" @\n @@\n".repeat(86)
+        + "0x41\n|_@\n|$@@\n0x42\n|_@\n| @@\n";
+    let mut font = OmmaFont::parse(&font).unwrap();
+    assert!(font.layout() == FontLayout::Smushing && font.has_glyph('A'));
+    let rows = |font: &OmmaFont, text: &str| Banner::new(text).font(font).rows();
+    // Big X joins \/ into Y and /\ into |
+    assert!(rows(&font, "//") == vec!["/Y\\", "\\|/"]);
+    // Underscores give way to bars, equal characters merge, hardblanks keep glyphs apart
+    assert!(rows(&font, "BB") == vec!["||_", "|| "]);
+    assert!(rows(&font, "AA") == vec!["|_|_", "| | "]);
+
+    font.set_layout(FontLayout::Kerning);
+    assert!(rows(&font, "//") == vec!["/\\/\\", "\\/\\/"]);
+    font.set_layout(FontLayout::FullWidth);
+    assert!(rows(&font, "/ /").len() == 2 && rows(&font, "/ /")[0] == "/\\ /\\");
+
+    assert!(OmmaFont::parse("flf2a$ x").is_err());
+    assert!(OmmaFont::parse("flf2a$ 99999999999 1 1 0 0\n@@").is_err());
+    assert!(OmmaFont::parse("flf2a$ 1 1 1 0 99999999999\n@@").is_err());
+    assert!(OmmaFont::parse("nope").is_err());
+    assert!(OmmaFont::default_font().height() == 5);
+}
@@ -1,9 +1,14 @@
 use crate::cell::*;
 
-#[derive(Default, Clone)]
+#[derive(Clone)]
 #[allow(dead_code)]
 pub struct OmmaBorder {
     hidden: bool,
+    unmerged: bool,
+    side_top: bool,
+    side_bottom: bool,
+    side_left: bool,
+    side_right: bool,
     border_top: OmmaCell,
     border_bottom: OmmaCell,
     border_left: OmmaCell,
@@ -14,11 +19,22 @@ pub struct OmmaBorder {
     border_corner_br: OmmaCell,
 }
 
+impl Default for OmmaBorder {
+    fn default() -> Self {
+        Self::new_mono(&DEFAULT_CELL)
+    }
+}
+
 impl OmmaBorder {
     /// new creates an OmmaBorder with distinct sides and corner
     pub fn new(horiz: &OmmaCell, vert: &OmmaCell, corner: &OmmaCell) -> Self {
         OmmaBorder {
             hidden: false,
+            unmerged: false,
+            side_top: true,
+            side_bottom: true,
+            side_left: true,
+            side_right: true,
             border_top: horiz.clone(),
             border_bottom: horiz.clone(),
             border_left: vert.clone(),
@@ -34,6 +50,11 @@ impl OmmaBorder {
     pub fn new_mono(mono: &OmmaCell) -> Self {
         OmmaBorder {
             hidden: false,
+            unmerged: false,
+            side_top: true,
+            side_bottom: true,
+            side_left: true,
+            side_right: true,
             border_top: mono.clone(),
             border_bottom: mono.clone(),
             border_left: mono.clone(),
@@ -45,10 +66,51 @@ impl OmmaBorder {
         }
     }
 
+    /// sides picks which edges of the border are drawn. A lone edge runs through the corners.
+    pub fn sides(mut self, top: bool, bottom: bool, left: bool, right: bool) -> Self {
+        self.set_sides(top, bottom, left, right);
+        self
+    }
+
+    /// cell_at returns the border cell at x, y of a width by height frame, or None when x, y is
+    /// not on a drawn edge
+    pub fn cell_at(&self, x: usize, y: usize, width: usize, height: usize) -> Option<&OmmaCell> {
+        let top = self.side_top && y == 0;
+        let bottom = self.side_bottom && y + 1 == height;
+        let left = self.side_left && x == 0;
+        let right = self.side_right && x + 1 == width;
+        match (top, bottom, left, right) {
+            (true, _, true, _) => Some(&self.border_corner_tl),
+            (true, _, _, true) => Some(&self.border_corner_tr),
+            (_, true, true, _) => Some(&self.border_corner_bl),
+            (_, true, _, true) => Some(&self.border_corner_br),
+            (true, _, _, _) => Some(&self.border_top),
+            (_, true, _, _) => Some(&self.border_bottom),
+            (_, _, true, _) => Some(&self.border_left),
+            (_, _, _, true) => Some(&self.border_right),
+            _ => None,
+        }
+    }
+
     // Getters
     pub fn hidden(&self) -> bool {
         self.hidden
     }
+    pub fn unmerged(&self) -> bool {
+        self.unmerged
+    }
+    pub fn side_top(&self) -> bool {
+        self.side_top
+    }
+    pub fn side_bottom(&self) -> bool {
+        self.side_bottom
+    }
+    pub fn side_left(&self) -> bool {
+        self.side_left
+    }
+    pub fn side_right(&self) -> bool {
+        self.side_right
+    }
     pub fn border_bottom(&self) -> &OmmaCell {
         &self.border_bottom
     }
@@ -86,10 +148,76 @@ impl OmmaBorder {
     pub fn clear_hidden(&mut self) {
         self.hidden = false
     }
+
+    /// set_unmerged stops the border from joining with borders drawn beneath it
+    pub fn set_unmerged(&mut self) {
+        self.unmerged = true
+    }
+
+    pub fn clear_unmerged(&mut self) {
+        self.unmerged = false
+    }
+
+    pub fn set_sides(&mut self, top: bool, bottom: bool, left: bool, right: bool) {
+        self.side_top = top;
+        self.side_bottom = bottom;
+        self.side_left = left;
+        self.side_right = right;
+    }
+}
+
+/// preset builds a border from glyphs ordered top, bottom, left, right, tl, tr, bl, br
+const fn preset(glyphs: [char; 8]) -> OmmaBorder {
+    let [top, bottom, left, right, tl, tr, bl, br] = glyphs;
+    OmmaBorder {
+        hidden: false,
+        unmerged: false,
+        side_top: true,
+        side_bottom: true,
+        side_left: true,
+        side_right: true,
+        border_top: OmmaCell {
+            ch: top,
+            ..DEFAULT_CELL
+        },
+        border_bottom: OmmaCell {
+            ch: bottom,
+            ..DEFAULT_CELL
+        },
+        border_left: OmmaCell {
+            ch: left,
+            ..DEFAULT_CELL
+        },
+        border_right: OmmaCell {
+            ch: right,
+            ..DEFAULT_CELL
+        },
+        border_corner_tl: OmmaCell {
+            ch: tl,
+            ..DEFAULT_CELL
+        },
+        border_corner_tr: OmmaCell {
+            ch: tr,
+            ..DEFAULT_CELL
+        },
+        border_corner_bl: OmmaCell {
+            ch: bl,
+            ..DEFAULT_CELL
+        },
+        border_corner_br: OmmaCell {
+            ch: br,
+            ..DEFAULT_CELL
+        },
+    }
 }
 
 pub const BOX_HOLLOW_BORDER: OmmaBorder = OmmaBorder {
     hidden: false,
+    unmerged: false,
+    side_top: true,
+    side_bottom: true,
+    side_left: true,
+    side_right: true,
     border_top: BOX_HORIZ_HOLLOW_CELL,
     border_bottom: BOX_HORIZ_HOLLOW_CELL,
     border_left: BOX_VERT_HOLLOW_CELL,
@@ -99,3 +227,31 @@ pub const BOX_HOLLOW_BORDER: OmmaBorder = OmmaBorder {
     border_corner_bl: BOX_CORNER_BL_HOLLOW_CELL,
     border_corner_br: BOX_CORNER_BR_HOLLOW_CELL,
 };
+
+pub const BOX_DOUBLE_BORDER: OmmaBorder = BOX_HOLLOW_BORDER;
+
+pub const BOX_SINGLE_BORDER: OmmaBorder = preset(['─', '─', '│', '│', '┌', '┐', '└', '┘']);
+
+pub const BOX_ROUNDED_BORDER: OmmaBorder = preset(['─', '─', '│', '│', '╭', '╮', '╰', '╯']);
+
+pub const BOX_HEAVY_BORDER: OmmaBorder = preset(['━', '━', '┃', '┃', '┏', '┓', '┗', '┛']);
+
+pub const BOX_DASHED_BORDER: OmmaBorder = preset(['┄', '┄', '┆', '┆', '┌', '┐', '└', '┘']);
+
+/// Double horizontal edges with single vertical edges
+pub const BOX_DOUBLE_HORIZ_BORDER: OmmaBorder = preset(['═', '═', '│', '│', '╒', '╕', '╘', '╛']);
+
+/// Single horizontal edges with double vertical edges
+pub const BOX_DOUBLE_VERT_BORDER: OmmaBorder = preset(['─', '─', '║', '║', '╓', '╖', '╙', '╜']);
+
+pub const ASCII_BORDER: OmmaBorder = preset(['-', '-', '|', '|', '+', '+', '+', '+']);
+
+pub const BLOCK_BORDER: OmmaBorder = preset(['▀', '▄', '▌', '▐', '▛', '▜', '▙', '▟']);
+
+#[test]
+fn cell_at() {
+    let border = BOX_SINGLE_BORDER.sides(true, false, false, false);
+    assert!(border.cell_at(0, 0, 4, 3).map(|cell| cell.ch) == Some('─'));
+    assert!(border.cell_at(0, 1, 4, 3).is_none());
+    assert!(BOX_SINGLE_BORDER.cell_at(3, 2, 4, 3).map(|cell| cell.ch) == Some('┘'));
+}
@@ -0,0 +1,186 @@
+use crate::cell::OmmaCell;
+use crate::error::OmmaErr;
+
+/// OmmaRegion is a rectangle of cells copied out of a window's content buffer, to be pasted into
+/// the same or another window
+#[derive(Clone, PartialEq)]
+pub struct OmmaRegion {
+    width: usize,
+    height: usize,
+    cells: Vec<Vec<OmmaCell>>,
+}
+
+impl OmmaRegion {
+    pub fn new(width: usize, height: usize, cell: &OmmaCell) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![vec![cell.clone(); height]; width],
+        }
+    }
+
+    pub(crate) fn from_cells(cells: Vec<Vec<OmmaCell>>) -> Self {
+        Self {
+            width: cells.len(),
+            height: cells.first().map_or(0, |col| col.len()),
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&OmmaCell> {
+        self.cells.get(x).and_then(|col| col.get(y))
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, cell: &OmmaCell) -> Result<(), OmmaErr> {
+        if x >= self.width || y >= self.height {
+            return Err(OmmaErr::new(&format!(
+                "invalid region write to {}:{} (max {}:{})",
+                x, y, self.width, self.height
+            )));
+        }
+        self.cells[x][y] = cell.clone();
+        Ok(())
+    }
+}
+
+/// line_points returns the cells on the line from x0, y0 to x1, y1 inclusive that lie inside a
+/// width by height buffer, using Bresenham's algorithm. Lines reaching outside the buffer are
+/// clipped to it first with Liang-Barsky, so far off endpoints cost no more than the part drawn.
+pub fn line_points(
+    x0: isize,
+    y0: isize,
+    x1: isize,
+    y1: isize,
+    width: usize,
+    height: usize,
+) -> Vec<(isize, isize)> {
+    let Some(((x0, y0), (x1, y1))) = clip_line((x0, y0), (x1, y1), width, height) else {
+        return Vec::new();
+    };
+    let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+    let (step_x, step_y) = ((x1 - x0).signum(), (y1 - y0).signum());
+    let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+    let mut points = Vec::with_capacity(dx.max(-dy) as usize + 1);
+    loop {
+        points.push((x, y));
+        if x == x1 && y == y1 {
+            return points;
+        }
+        let err2 = 2 * err;
+        if err2 >= dy {
+            err += dy;
+            x += step_x;
+        }
+        if err2 <= dx {
+            err += dx;
+            y += step_y;
+        }
+    }
+}
+
+/// clip_line cuts the line from start to end down to the part inside a width by height buffer,
+/// returns None if it misses the buffer. Lines already inside are returned as they are, slanted
+/// ones reaching outside are cut in floating point and lose precision for endpoints near the ends
+/// of isize.
+fn clip_line(
+    start: (isize, isize),
+    end: (isize, isize),
+    width: usize,
+    height: usize,
+) -> Option<((isize, isize), (isize, isize))> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let (max_x, max_y) = ((width - 1) as isize, (height - 1) as isize);
+    let inside = |(x, y): (isize, isize)| (0..=max_x).contains(&x) && (0..=max_y).contains(&y);
+    if inside(start) && inside(end) {
+        return Some((start, end));
+    }
+    // Rows and columns are their own bounding box, clamping them is exact
+    if start.0 == end.0 || start.1 == end.1 {
+        let hits = start.0.min(end.0) <= max_x
+            && start.0.max(end.0) >= 0
+            && start.1.min(end.1) <= max_y
+            && start.1.max(end.1) >= 0;
+        let clamp = |(x, y): (isize, isize)| (x.clamp(0, max_x), y.clamp(0, max_y));
+        return hits.then(|| (clamp(start), clamp(end)));
+    }
+    let (max_x, max_y) = (max_x as f64, max_y as f64);
+    let (x0, y0) = (start.0 as f64, start.1 as f64);
+    let (dx, dy) = (end.0 as f64 - x0, end.1 as f64 - y0);
+    let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+    for (p, q) in [(-dx, x0), (dx, max_x - x0), (-dy, y0), (dy, max_y - y0)] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    let point = |t: f64| {
+        (
+            (x0 + dx * t).round().clamp(0.0, max_x) as isize,
+            (y0 + dy * t).round().clamp(0.0, max_y) as isize,
+        )
+    };
+    Some((point(t0), point(t1)))
+}
+
+/// ellipse_rows returns a row for each dy in -ry..=ry of an ellipse centered on 0, 0 as
+/// (dy, inner, outer). A filled ellipse covers -outer..=outer, its outline covers inner..=outer
+/// and its mirror, which keeps the outline connected where it runs steep.
+pub(crate) fn ellipse_rows(rx: usize, ry: usize) -> Vec<(isize, isize, isize)> {
+    let half_width = |dy: usize| -> isize {
+        if dy > ry {
+            return -1;
+        }
+        if ry == 0 {
+            return rx as isize;
+        }
+        let t = dy as f64 / ry as f64;
+        (rx as f64 * (1.0 - t * t).sqrt()).round() as isize
+    };
+    (-(ry as isize)..=ry as isize)
+        .map(|dy| {
+            let outer = half_width(dy.unsigned_abs());
+            let inner = (half_width(dy.unsigned_abs() + 1) + 1).min(outer);
+            (dy, inner, outer)
+        })
+        .collect()
+}
+
+#[test]
+fn shapes() {
+    assert!(line_points(0, 0, 3, 1, 4, 4) == vec![(0, 0), (1, 0), (2, 1), (3, 1)]);
+    assert!(line_points(2, 2, 2, 0, 4, 4) == vec![(2, 2), (2, 1), (2, 0)]);
+    assert!(line_points(1, 1, 1, 1, 4, 4) == vec![(1, 1)]);
+
+    // Lines are clipped before they are stepped, so far off endpoints neither allocate nor
+    // overflow
+    assert!(line_points(-2, 1, 5, 1, 4, 4) == vec![(0, 1), (1, 1), (2, 1), (3, 1)]);
+    assert!(line_points(isize::MIN, 0, isize::MAX, 0, 3, 1) == vec![(0, 0), (1, 0), (2, 0)]);
+    let far = 1_000_000_000_000;
+    assert!(line_points(-far, -far, far, far, 2, 2) == vec![(0, 0), (1, 1)]);
+    assert!(line_points(isize::MIN, isize::MIN, isize::MAX, isize::MAX, 2, 2).len() <= 2);
+    assert!(line_points(5, 0, 9, 3, 4, 4).is_empty() && line_points(0, 0, 1, 1, 0, 0).is_empty());
+
+    // The widest row of an ellipse spans the x radius, its top and bottom a single cell
+    let rows = ellipse_rows(4, 2);
+    assert!(rows.len() == 5);
+    assert!(rows[0] == (-2, 0, 0) && rows[2].2 == 4);
+    assert!(ellipse_rows(3, 0) == vec![(0, 0, 3)]);
+}
@@ -1,13 +1,35 @@
-use crate::color::OmmaColor;
+use crate::color::{CLEAR, OmmaColor};
+use crate::theme::OmmaRole;
+use crate::unicode;
+use std::rc::Rc;
+
 pub const EMPTY: char = '\0';
+/// CONTINUATION fills the column covered by the right half of a wide character in the cell before
+pub const CONTINUATION: char = '\u{FFFF}';
+/// INHERIT keeps the glyph and attrs of the cell underneath while the cell's colors still apply
+pub const INHERIT: char = '\u{FFFE}';
+
+/// Attribute bits for OmmaCell::attrs
+pub const ATTR_BOLD: u16 = 1 << 0;
+pub const ATTR_DIM: u16 = 1 << 1;
+pub const ATTR_ITALIC: u16 = 1 << 2;
+pub const ATTR_UNDERLINE: u16 = 1 << 3;
+pub const ATTR_BLINK: u16 = 1 << 4;
+pub const ATTR_REVERSE: u16 = 1 << 5;
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[allow(dead_code)]
 pub struct OmmaCell {
     pub ch: char,
     pub fg: Option<OmmaColor>,
     pub bg: Option<OmmaColor>,
     pub attrs: u16,
+    /// The rest of the grapheme cluster started by ch, such as combining marks or an emoji
+    /// sequence
+    pub tail: Option<Rc<str>>,
+    /// The theme role the cell is drawn in, a theme defining the role restyles the cell when it is
+    /// composited
+    pub role: Option<OmmaRole>,
 }
 
 impl Default for OmmaCell {
@@ -18,13 +40,99 @@ impl Default for OmmaCell {
 
 impl OmmaCell {
     pub fn new(ch: char, fg: Option<OmmaColor>, bg: Option<OmmaColor>, attrs: u16) -> Self {
-        OmmaCell { ch, fg, bg, attrs }
+        OmmaCell {
+            ch,
+            fg,
+            bg,
+            attrs,
+            tail: None,
+            role: None,
+        }
+    }
+
+    /// themed creates a blank cell drawn in role, such as to fill a window with the background
+    pub fn themed(role: OmmaRole) -> Self {
+        OmmaCell {
+            role: Some(role),
+            ..Self::default()
+        }
+    }
+
+    /// width returns the number of columns the cell's character takes
+    pub fn width(&self) -> usize {
+        match (self.ch, &self.tail) {
+            (EMPTY | CONTINUATION, _) => 0,
+            (ch, None) => unicode::char_width(ch),
+            (ch, Some(tail)) => unicode::cluster_width(&format!("{}{}", ch, tail)),
+        }
+    }
+
+    /// is_wide returns true if the cell's character also covers the column after it
+    pub fn is_wide(&self) -> bool {
+        self.width() == 2
+    }
+
+    /// glyph returns the full grapheme cluster drawn by the cell
+    pub fn glyph(&self) -> String {
+        let mut glyph = self.ch.to_string();
+        if let Some(tail) = &self.tail {
+            glyph.push_str(tail);
+        }
+        glyph
     }
 
     pub fn transparent() -> Self {
         Self::new(EMPTY, None, None, 0)
     }
 
+    /// over composites the cell on top of under field by field. An INHERIT glyph keeps under's
+    /// glyph and attrs, and translucent colors are blended over under's colors. Colors over
+    /// under's terminal default can not be blended and show if they are at least half opaque.
+    pub fn over(&self, under: &OmmaCell) -> OmmaCell {
+        let blend = |over: Option<OmmaColor>, under: Option<OmmaColor>| match (over, under) {
+            (Some(over), _) if over.is_opaque() => Some(over),
+            (Some(over), Some(under)) => Some(over.over(&under)),
+            (Some(over), None) if over.a >= 128 => Some(over.with_alpha(255)),
+            (Some(_), None) => None,
+            (None, _) => None,
+        };
+        let glyph = if self.ch == INHERIT { under } else { self };
+        OmmaCell {
+            ch: glyph.ch,
+            fg: blend(self.fg, under.fg),
+            bg: blend(self.bg, under.bg),
+            attrs: glyph.attrs,
+            tail: glyph.tail.clone(),
+            role: glyph.role,
+        }
+    }
+
+    /// is_composite returns true if drawing the cell depends on the cell underneath
+    pub fn is_composite(&self) -> bool {
+        self.ch == INHERIT
+            || self.fg.is_some_and(|fg| !fg.is_opaque())
+            || self.bg.is_some_and(|bg| !bg.is_opaque())
+    }
+
+    /// attrcode returns the ANSI escape code to reset the terminal style and apply attrs
+    pub(crate) fn attrcode(&self) -> String {
+        let mut code = "\x1b[0".to_string();
+        for (bit, sgr) in [
+            (ATTR_BOLD, 1),
+            (ATTR_DIM, 2),
+            (ATTR_ITALIC, 3),
+            (ATTR_UNDERLINE, 4),
+            (ATTR_BLINK, 5),
+            (ATTR_REVERSE, 7),
+        ] {
+            if self.attrs & bit != 0 {
+                code.push_str(&format!(";{}", sgr));
+            }
+        }
+        code.push('m');
+        code
+    }
+
     /// fgcode returns the ANSI escape code to set the foreground color or reset on None
     pub(crate) fn fgcode(&self) -> String {
         if let Some(fg) = &self.fg {
@@ -50,11 +158,40 @@ fn new() {
     assert!(ommacell.ch == ' ');
 }
 
+#[test]
+fn over() {
+    use crate::color::{BLACK, BLUE, RED};
+    let under = OmmaCell::new('#', Some(RED), Some(BLACK), ATTR_BOLD);
+    let tint = OmmaCell {
+        bg: Some(BLUE.with_alpha(128)),
+        ..OVERLAY_CELL
+    };
+    let tinted = tint.over(&under);
+    assert!(tinted.ch == '#' && tinted.fg == Some(RED) && tinted.attrs == ATTR_BOLD);
+    assert!(tinted.bg == Some(OmmaColor::new(0, 0, 128)));
+    let glyph = OmmaCell {
+        ch: 'x',
+        fg: Some(BLUE),
+        ..OVERLAY_CELL
+    };
+    let marked = glyph.over(&under);
+    assert!(marked.ch == 'x' && marked.fg == Some(BLUE) && marked.bg == Some(BLACK));
+    assert!(marked.attrs == 0);
+}
+
+#[test]
+fn attrcode() {
+    let ommacell = OmmaCell::new('x', None, None, ATTR_BOLD | ATTR_REVERSE);
+    assert!(ommacell.attrcode() == "\x1b[0;1;7m");
+}
+
 pub const DEFAULT_CELL: OmmaCell = OmmaCell {
     ch: ' ',
     fg: None,
     bg: None,
     attrs: 0,
+    tail: None,
+    role: None,
 };
 
 pub const BLANK_CELL: OmmaCell = OmmaCell { ..DEFAULT_CELL };
@@ -64,6 +201,15 @@ pub const EMPTY_CELL: OmmaCell = OmmaCell {
     ..DEFAULT_CELL
 };
 
+/// OVERLAY_CELL leaves every field of the cell underneath as it is, set a glyph or a translucent
+/// color on top to change only that part
+pub const OVERLAY_CELL: OmmaCell = OmmaCell {
+    ch: INHERIT,
+    fg: Some(CLEAR),
+    bg: Some(CLEAR),
+    ..DEFAULT_CELL
+};
+
 pub const PLAYER_CELL: OmmaCell = OmmaCell {
     ch: '@',
     ..DEFAULT_CELL
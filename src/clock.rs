@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+enum ClockSource {
+    /// Every tick advances by the same step, used headless so frames are reproducible
+    Fixed(Duration),
+    /// Ticks follow the wall clock
+    Real(Instant),
+}
+
+/// OmmaClock counts frames and the time elapsed since the session started
+#[derive(Debug, Clone, Copy)]
+pub struct OmmaClock {
+    source: ClockSource,
+    frame: u64,
+    elapsed: Duration,
+    delta: Duration,
+}
+
+impl OmmaClock {
+    /// fixed creates a clock that advances by step on every tick
+    pub fn fixed(step: Duration) -> Self {
+        OmmaClock {
+            source: ClockSource::Fixed(step),
+            frame: 0,
+            elapsed: Duration::ZERO,
+            delta: Duration::ZERO,
+        }
+    }
+
+    /// real creates a clock that follows the wall clock
+    pub fn real() -> Self {
+        OmmaClock {
+            source: ClockSource::Real(Instant::now()),
+            frame: 0,
+            elapsed: Duration::ZERO,
+            delta: Duration::ZERO,
+        }
+    }
+
+    /// tick advances the clock by one frame and returns the time since the previous tick
+    pub fn tick(&mut self) -> Duration {
+        let elapsed = match self.source {
+            ClockSource::Fixed(step) => self.elapsed + step,
+            ClockSource::Real(start) => start.elapsed(),
+        };
+        self.delta = elapsed.saturating_sub(self.elapsed);
+        self.elapsed = elapsed;
+        self.frame += 1;
+        self.delta
+    }
+
+    // Getters
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+}
+
+#[test]
+fn fixed() {
+    let mut clock = OmmaClock::fixed(Duration::from_millis(50));
+    clock.tick();
+    clock.tick();
+    assert!(clock.frame() == 2);
+    assert!(clock.elapsed() == Duration::from_millis(100));
+}
@@ -1,9 +1,15 @@
-#[derive(Clone)]
+use crate::error::OmmaErr;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[allow(dead_code)]
 pub struct OmmaColor {
     pub r: u8,
     pub g: u8,
     pub b: u8,
+    /// Opacity from 0 (fully transparent) to 255 (opaque). Translucent colors are blended over
+    /// the colors already in the back plane when a cell is composited.
+    pub a: u8,
 }
 
 impl Default for OmmaColor {
@@ -12,9 +18,383 @@ impl Default for OmmaColor {
     }
 }
 
+/// ColorSpace picks how colors are mixed. Srgb interpolates the stored channels, Linear mixes light
+/// the way a monitor emits it and Oklab mixes by perceived lightness and hue, which avoids the
+/// muddy midpoints of the other two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    Linear,
+    Oklab,
+}
+
+/// OmmaColor parses from `#rrggbb`, `#rgb`, their `#rrggbbaa` and `#rgba` forms with alpha or a
+/// CSS color name such as "rebeccapurple" or "transparent". Names
+/// ignore case, spaces, dashes and underscores. The crate's own constants win where they overlap
+/// with CSS, so "green" is GREEN and "dark_grey" is DARK_GREY.
+impl FromStr for OmmaColor {
+    type Err = OmmaErr;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let color = if text.starts_with('#') {
+            Self::from_hex(text)
+        } else {
+            Self::from_name(text)
+        };
+        color.ok_or_else(|| OmmaErr::new(&format!("invalid color \"{}\"", text)))
+    }
+}
+
 impl OmmaColor {
+    /// new creates an opaque color
     pub fn new(r: u8, g: u8, b: u8) -> Self {
-        Self { r, g, b }
+        Self { r, g, b, a: 255 }
+    }
+
+    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// with_alpha returns the color with its opacity replaced by a
+    pub fn with_alpha(&self, a: u8) -> OmmaColor {
+        OmmaColor { a, ..*self }
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.a == 255
+    }
+
+    /// over composites self on top of under by self's alpha
+    pub fn over(&self, under: &OmmaColor) -> OmmaColor {
+        if self.a == 255 {
+            return *self;
+        }
+        let over_a = self.a as f64 / 255.0;
+        let under_a = under.a as f64 / 255.0;
+        let a = over_a + under_a * (1.0 - over_a);
+        if a == 0.0 {
+            return CLEAR;
+        }
+        let mix = |over: u8, under: u8| {
+            ((over as f64 * over_a + under as f64 * under_a * (1.0 - over_a)) / a).round() as u8
+        };
+        OmmaColor::rgba(
+            mix(self.r, under.r),
+            mix(self.g, under.g),
+            mix(self.b, under.b),
+            (a * 255.0).round() as u8,
+        )
+    }
+
+    /// from_hex reads `#rrggbb`, `#rrggbbaa` or the short forms `#rgb` and `#rgba`, the leading #
+    /// is optional
+    pub fn from_hex(hex: &str) -> Option<OmmaColor> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.is_ascii() {
+            return None;
+        }
+        let digits = match hex.len() {
+            3 | 4 => 1,
+            6 | 8 => 2,
+            _ => return None,
+        };
+        let channel = |index: usize| {
+            let value = u8::from_str_radix(hex.get(index * digits..(index + 1) * digits)?, 16);
+            value
+                .ok()
+                .map(|value| if digits == 1 { value * 17 } else { value })
+        };
+        let a = if digits * 4 == hex.len() {
+            channel(3)?
+        } else {
+            255
+        };
+        Some(OmmaColor::rgba(channel(0)?, channel(1)?, channel(2)?, a))
+    }
+
+    /// from_name looks up a color by name, see the FromStr implementation for the rules
+    pub fn from_name(name: &str) -> Option<OmmaColor> {
+        let name: String = name
+            .chars()
+            .filter(|ch| !matches!(ch, ' ' | '-' | '_'))
+            .map(|ch| ch.to_ascii_lowercase())
+            .collect();
+        if name == "transparent" {
+            return Some(CLEAR);
+        }
+        NAMED
+            .iter()
+            .find(|(named, _)| *named == name)
+            .map(|(_, rgb)| OmmaColor::new((rgb >> 16) as u8, (rgb >> 8) as u8, *rgb as u8))
+    }
+
+    /// to_hex formats the color as `#rrggbb`, or `#rrggbbaa` if it is not opaque
+    pub fn to_hex(&self) -> String {
+        let hex = format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b);
+        if self.is_opaque() {
+            hex
+        } else {
+            format!("{}{:02x}", hex, self.a)
+        }
+    }
+
+    /// from_hsl builds an opaque color from a hue in degrees and saturation and lightness in 0.0..=1.0
+    pub fn from_hsl(h: f64, s: f64, l: f64) -> OmmaColor {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Self::from_hue(h, chroma, l - chroma / 2.0)
+    }
+
+    /// to_hsl returns the hue in degrees 0.0..360.0 and the saturation and lightness in 0.0..=1.0
+    pub fn to_hsl(&self) -> (f64, f64, f64) {
+        let (h, max, min) = self.hue();
+        let l = (max + min) / 2.0;
+        let s = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        (h, s, l)
+    }
+
+    /// from_hsv builds an opaque color from a hue in degrees and saturation and value in 0.0..=1.0
+    pub fn from_hsv(h: f64, s: f64, v: f64) -> OmmaColor {
+        let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let chroma = v * s;
+        Self::from_hue(h, chroma, v - chroma)
+    }
+
+    /// to_hsv returns the hue in degrees 0.0..360.0 and the saturation and value in 0.0..=1.0
+    pub fn to_hsv(&self) -> (f64, f64, f64) {
+        let (h, max, min) = self.hue();
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (h, s, max)
+    }
+
+    /// hue returns the hue in degrees and the largest and smallest channel in 0.0..=1.0
+    fn hue(&self) -> (f64, f64, f64) {
+        let (r, g, b) = (
+            self.r as f64 / 255.0,
+            self.g as f64 / 255.0,
+            self.b as f64 / 255.0,
+        );
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let h = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (h, max, min)
+    }
+
+    /// from_hue builds a color from a hue in degrees, its chroma and the amount added to every
+    /// channel, shared by from_hsl and from_hsv
+    fn from_hue(h: f64, chroma: f64, base: f64) -> OmmaColor {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let byte = |c: f64| ((c + base) * 255.0).round().clamp(0.0, 255.0) as u8;
+        OmmaColor::new(byte(r), byte(g), byte(b))
+    }
+
+    /// lighten raises the HSL lightness by amount, 1.0 always gives white
+    pub fn lighten(&self, amount: f64) -> OmmaColor {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s, l + amount).with_alpha(self.a)
+    }
+
+    /// darken lowers the HSL lightness by amount, 1.0 always gives black
+    pub fn darken(&self, amount: f64) -> OmmaColor {
+        self.lighten(-amount)
+    }
+
+    /// saturate raises the HSL saturation by amount, negative amounts wash the color out
+    pub fn saturate(&self, amount: f64) -> OmmaColor {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s + amount, l).with_alpha(self.a)
+    }
+
+    /// desaturate lowers the HSL saturation by amount, 1.0 always gives a grey
+    pub fn desaturate(&self, amount: f64) -> OmmaColor {
+        self.saturate(-amount)
+    }
+
+    /// lerp linearly interpolates between self and other, t is clamped to 0.0..=1.0
+    pub fn lerp(&self, other: &OmmaColor, t: f64) -> OmmaColor {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        OmmaColor::rgba(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+            mix(self.a, other.a),
+        )
+    }
+
+    /// blend interpolates between self and other within space, t is clamped to 0.0..=1.0
+    pub fn blend(&self, other: &OmmaColor, t: f64, space: ColorSpace) -> OmmaColor {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: [f64; 3], b: [f64; 3]| {
+            [
+                a[0] + (b[0] - a[0]) * t,
+                a[1] + (b[1] - a[1]) * t,
+                a[2] + (b[2] - a[2]) * t,
+            ]
+        };
+        let a = (self.a as f64 + (other.a as f64 - self.a as f64) * t).round() as u8;
+        match space {
+            ColorSpace::Srgb => self.lerp(other, t),
+            ColorSpace::Linear => {
+                Self::from_linear(mix(self.linear(), other.linear())).with_alpha(a)
+            }
+            ColorSpace::Oklab => Self::from_oklab(mix(self.oklab(), other.oklab())).with_alpha(a),
+        }
+    }
+
+    /// linear returns the channels as linear light in 0.0..=1.0
+    fn linear(&self) -> [f64; 3] {
+        let decode = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        [decode(self.r), decode(self.g), decode(self.b)]
+    }
+
+    fn from_linear(rgb: [f64; 3]) -> OmmaColor {
+        let encode = |c: f64| {
+            let c = c.clamp(0.0, 1.0);
+            let c = if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            };
+            (c * 255.0).round() as u8
+        };
+        OmmaColor::new(encode(rgb[0]), encode(rgb[1]), encode(rgb[2]))
+    }
+
+    /// oklab returns the color's lightness, green-red and blue-yellow axes in the Oklab space
+    fn oklab(&self) -> [f64; 3] {
+        let [r, g, b] = self.linear();
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        [
+            0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        ]
+    }
+
+    fn from_oklab(lab: [f64; 3]) -> OmmaColor {
+        let [lightness, a, b] = lab;
+        let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+        Self::from_linear([
+            4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+            -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+            -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+        ])
+    }
+
+    /// multiply scales each color channel of self by the matching channel of other
+    pub fn multiply(&self, other: &OmmaColor) -> OmmaColor {
+        let mul = |a: u8, b: u8| ((a as u16 * b as u16 + 127) / 255) as u8;
+        OmmaColor::rgba(
+            mul(self.r, other.r),
+            mul(self.g, other.g),
+            mul(self.b, other.b),
+            self.a,
+        )
+    }
+
+    /// luma returns the perceived brightness of the color
+    pub fn luma(&self) -> u8 {
+        (0.299 * self.r as f64 + 0.587 * self.g as f64 + 0.114 * self.b as f64).round() as u8
+    }
+}
+
+/// OmmaGradient maps positions in 0.0..=1.0 to colors blended between stops. Positions before the
+/// first stop or after the last take that stop's color.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OmmaGradient {
+    stops: Vec<(f64, OmmaColor)>,
+    space: ColorSpace,
+}
+
+impl OmmaGradient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// even creates a gradient with colors spread evenly from 0.0 to 1.0
+    pub fn even(colors: &[OmmaColor]) -> Self {
+        let last = colors.len().saturating_sub(1).max(1) as f64;
+        colors
+            .iter()
+            .enumerate()
+            .fold(Self::new(), |gradient, (index, color)| {
+                gradient.stop(index as f64 / last, color)
+            })
+    }
+
+    /// stop adds a color at position, clamped to 0.0..=1.0. Stops at the same position keep the
+    /// order they were added in, giving a hard edge.
+    pub fn stop(mut self, position: f64, color: &OmmaColor) -> Self {
+        let position = position.clamp(0.0, 1.0);
+        let index = self.stops.partition_point(|(at, _)| *at <= position);
+        self.stops.insert(index, (position, *color));
+        self
+    }
+
+    /// space sets the color space stops are blended in
+    pub fn space(mut self, space: ColorSpace) -> Self {
+        self.space = space;
+        self
+    }
+
+    pub fn stops(&self) -> &[(f64, OmmaColor)] {
+        &self.stops
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stops.is_empty()
+    }
+
+    /// sample returns the color at position t, or None for a gradient without stops
+    pub fn sample(&self, t: f64) -> Option<OmmaColor> {
+        let t = t.clamp(0.0, 1.0);
+        let after = self.stops.partition_point(|(at, _)| *at <= t);
+        match (
+            after.checked_sub(1).map(|index| &self.stops[index]),
+            self.stops.get(after),
+        ) {
+            (None, None) => None,
+            (Some((_, color)), None) | (None, Some((_, color))) => Some(*color),
+            (Some(&(from_at, from)), Some(&(to_at, to))) => {
+                let local = (t - from_at) / (to_at - from_at);
+                Some(from.blend(&to, local, self.space))
+            }
+        }
     }
 }
 
@@ -22,23 +402,257 @@ pub const WHITE: OmmaColor = OmmaColor {
     r: 255,
     g: 255,
     b: 255,
+    a: 255,
 };
 
 pub const LIGHT_GREY: OmmaColor = OmmaColor {
     r: 200,
     g: 200,
     b: 200,
+    a: 255,
 };
 pub const DARK_GREY: OmmaColor = OmmaColor {
     r: 100,
     g: 100,
     b: 100,
+    a: 255,
+};
+
+pub const BLACK: OmmaColor = OmmaColor {
+    r: 0,
+    g: 0,
+    b: 0,
+    a: 255,
+};
+
+pub const RED: OmmaColor = OmmaColor {
+    r: 255,
+    g: 0,
+    b: 0,
+    a: 255,
 };
 
-pub const BLACK: OmmaColor = OmmaColor { r: 0, g: 0, b: 0 };
+pub const GREEN: OmmaColor = OmmaColor {
+    r: 0,
+    g: 255,
+    b: 0,
+    a: 255,
+};
+
+pub const BLUE: OmmaColor = OmmaColor {
+    r: 0,
+    g: 0,
+    b: 255,
+    a: 255,
+};
 
-pub const RED: OmmaColor = OmmaColor { r: 255, g: 0, b: 0 };
+/// CLEAR is fully transparent, a cell field colored CLEAR keeps the color underneath
+pub const CLEAR: OmmaColor = OmmaColor {
+    r: 0,
+    g: 0,
+    b: 0,
+    a: 0,
+};
 
-pub const GREEN: OmmaColor = OmmaColor { r: 0, g: 255, b: 0 };
+/// Names understood by OmmaColor::from_name, the crate's constants first and then the CSS colors
+const NAMED: [(&str, u32); 148] = [
+    ("white", 0xffffff),
+    ("lightgrey", 0xc8c8c8),
+    ("lightgray", 0xc8c8c8),
+    ("darkgrey", 0x646464),
+    ("darkgray", 0x646464),
+    ("black", 0x000000),
+    ("red", 0xff0000),
+    ("green", 0x00ff00),
+    ("blue", 0x0000ff),
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("blanchedalmond", 0xffebcd),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgreen", 0x006400),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("grey", 0x808080),
+    ("greenyellow", 0xadff2f),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgreen", 0x90ee90),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
 
-pub const BLUE: OmmaColor = OmmaColor { r: 0, g: 0, b: 255 };
+#[test]
+fn parse() {
+    assert!("#FF8000".parse::<OmmaColor>().ok() == Some(OmmaColor::new(255, 128, 0)));
+    assert!("#0f8".parse::<OmmaColor>().ok() == Some(OmmaColor::new(0, 255, 136)));
+    assert!("Rebecca Purple".parse::<OmmaColor>().ok() == Some(OmmaColor::new(102, 51, 153)));
+    assert!("dark_grey".parse::<OmmaColor>().ok() == Some(DARK_GREY));
+    assert!("#12345".parse::<OmmaColor>().is_err());
+    assert!("mauve".parse::<OmmaColor>().is_err());
+    assert!(OmmaColor::new(18, 52, 86).to_hex() == "#123456");
+    assert!("#ff000080".parse::<OmmaColor>().ok() == Some(RED.with_alpha(128)));
+    assert!("#f008".parse::<OmmaColor>().ok() == Some(RED.with_alpha(136)));
+    assert!("transparent".parse::<OmmaColor>().ok() == Some(CLEAR));
+    assert!(RED.with_alpha(128).to_hex() == "#ff000080");
+}
+
+#[test]
+fn hsl_hsv() {
+    let orange = OmmaColor::new(255, 128, 0);
+    let (h, s, l) = orange.to_hsl();
+    assert!((h - 30.1).abs() < 0.1 && s == 1.0 && (l - 0.5).abs() < 0.01);
+    assert!(OmmaColor::from_hsl(h, s, l) == orange);
+    let (h, s, v) = orange.to_hsv();
+    assert!(OmmaColor::from_hsv(h, s, v) == orange);
+    assert!(OmmaColor::from_hsv(240.0, 1.0, 1.0) == BLUE);
+    assert!(RED.lighten(1.0) == WHITE && RED.darken(1.0) == BLACK);
+    assert!(RED.darken(0.25) == OmmaColor::new(128, 0, 0));
+    let grey = RED.desaturate(1.0);
+    assert!(grey.r == grey.g && grey.g == grey.b);
+}
+
+#[test]
+fn gradient() {
+    assert!(OmmaGradient::new().sample(0.5).is_none());
+    let gradient = OmmaGradient::even(&[RED, GREEN, BLUE]);
+    assert!(gradient.sample(0.0) == Some(RED));
+    assert!(gradient.sample(0.25) == Some(OmmaColor::new(128, 128, 0)));
+    assert!(gradient.sample(1.0) == Some(BLUE));
+    let hard = OmmaGradient::new()
+        .stop(0.5, &WHITE)
+        .stop(0.5, &BLACK)
+        .stop(0.25, &RED);
+    assert!(hard.sample(0.0) == Some(RED));
+    assert!(hard.sample(0.49).is_some_and(|color| color.g > 200));
+    assert!(hard.sample(0.75) == Some(BLACK));
+    // Linear light keeps more brightness in the middle than stored channel values
+    let srgb = BLACK.blend(&WHITE, 0.5, ColorSpace::Srgb);
+    let linear = BLACK.blend(&WHITE, 0.5, ColorSpace::Linear);
+    let oklab = RED.blend(&BLUE, 0.0, ColorSpace::Oklab);
+    assert!(linear.r > srgb.r && oklab == RED);
+}
@@ -0,0 +1,120 @@
+use crate::error::OmmaErr;
+use crate::session::Session;
+use std::time::Duration;
+
+/// EffectTarget is what an effect retires once its lifetime runs out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectTarget {
+    Window(u32),
+    Object(u32),
+    /// Nothing is retired, the effect only runs its update
+    Detached,
+}
+
+/// EffectTick is handed to an effect's update once per session tick
+#[derive(Debug, Clone, Copy)]
+pub struct EffectTick {
+    pub target: EffectTarget,
+    /// Time since the effect was submitted
+    pub age: Duration,
+    /// Time since the previous tick
+    pub delta: Duration,
+    pub frame: u64,
+}
+
+/// EffectUpdate runs every tick while the effect is alive. Returning false ends the effect early.
+pub type EffectUpdate = Box<dyn FnMut(&mut Session, &EffectTick) -> Result<bool, OmmaErr>>;
+
+/// Effect ties a window or object to a lifetime and a per frame update
+pub(crate) struct Effect {
+    id: u32,
+    target: EffectTarget,
+    lifetime: Option<Duration>,
+    age: Duration,
+    update: Option<EffectUpdate>,
+}
+
+impl Effect {
+    pub(crate) fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// step advances the effect by delta and runs its update, returns false once it has expired
+    pub(crate) fn step(
+        &mut self,
+        session: &mut Session,
+        delta: Duration,
+        frame: u64,
+    ) -> Result<bool, OmmaErr> {
+        self.age += delta;
+        let tick = EffectTick {
+            target: self.target,
+            age: self.age,
+            delta,
+            frame,
+        };
+        let mut alive = match &mut self.update {
+            Some(update) => update(session, &tick)?,
+            None => true,
+        };
+        if let Some(lifetime) = self.lifetime {
+            alive &= self.age < lifetime;
+        }
+        Ok(alive)
+    }
+
+    /// retire cleans up the effect's target
+    pub(crate) fn retire(&self, session: &mut Session) -> Result<(), OmmaErr> {
+        match self.target {
+            EffectTarget::Window(id) => session.retire_window(id),
+            EffectTarget::Object(id) => session.retire_object(id),
+            EffectTarget::Detached => Ok(()),
+        }
+    }
+}
+
+pub struct EffectBuilder {
+    target: EffectTarget,
+    lifetime: Option<Duration>,
+    update: Option<EffectUpdate>,
+}
+
+impl EffectBuilder {
+    pub fn new(target: EffectTarget) -> Self {
+        EffectBuilder {
+            target,
+            lifetime: None,
+            update: None,
+        }
+    }
+
+    /// lifetime retires the target once the effect has been alive this long. Omitting lifetime
+    /// keeps the effect running until its update returns false.
+    pub fn lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = Some(lifetime);
+        self
+    }
+
+    /// update sets a callback run on every session tick
+    pub fn update<F>(mut self, update: F) -> Self
+    where
+        F: FnMut(&mut Session, &EffectTick) -> Result<bool, OmmaErr> + 'static,
+    {
+        self.update = Some(Box::new(update));
+        self
+    }
+
+    /// submit adds the effect into the session, it first runs on the next tick. Returns the
+    /// effect id used to cancel it.
+    pub fn submit(self, session: &mut Session) -> Result<u32, OmmaErr> {
+        let id = crate::next_effect_id()?;
+        session.register_effect(Effect {
+            id,
+            target: self.target,
+            lifetime: self.lifetime,
+            age: Duration::ZERO,
+            update: self.update,
+        });
+        Ok(id)
+    }
+}
@@ -17,6 +17,11 @@ impl OmmaErr {
             body: body.to_string(),
         }
     }
+
+    /// body returns the error message without the OmmaErr prefix
+    pub fn body(&self) -> &str {
+        &self.body
+    }
 }
 
 impl From<std::io::Error> for OmmaErr {
@@ -0,0 +1,158 @@
+use crate::cell::{ATTR_DIM, BLANK_CELL, OmmaCell};
+use crate::color::{BLACK, OmmaColor};
+use crate::error::OmmaErr;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OmmaVisibility {
+    /// Never seen, drawn blank
+    Hidden,
+    /// Seen before but not currently in view, drawn dimmed
+    Remembered,
+    /// In view, drawn untouched
+    #[default]
+    Visible,
+}
+
+/// OmmaMask is a grid of visibility states laid over a window's content buffer. Clones share the
+/// grid until one of them is modified.
+#[derive(Clone)]
+pub struct OmmaMask {
+    width: usize,
+    height: usize,
+    cells: Rc<Vec<OmmaVisibility>>,
+}
+
+impl OmmaMask {
+    pub fn new(width: usize, height: usize, fill: OmmaVisibility) -> Self {
+        OmmaMask {
+            width,
+            height,
+            cells: Rc::new(vec![fill; width * height]),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// get returns the visibility at x, y, positions outside the mask are visible
+    pub fn get(&self, x: isize, y: isize) -> OmmaVisibility {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return OmmaVisibility::Visible;
+        }
+        self.cells[y as usize * self.width + x as usize]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, visibility: OmmaVisibility) -> Result<(), OmmaErr> {
+        if x >= self.width || y >= self.height {
+            return Err(OmmaErr::new(&format!(
+                "invalid mask write to {}:{} (max {}:{})",
+                x, y, self.width, self.height
+            )));
+        }
+        Rc::make_mut(&mut self.cells)[y * self.width + x] = visibility;
+        Ok(())
+    }
+
+    /// fill sets every cell of the mask to visibility
+    pub fn fill(&mut self, visibility: OmmaVisibility) {
+        Rc::make_mut(&mut self.cells).fill(visibility);
+    }
+
+    /// forget turns every visible cell into a remembered one, typically before marking the cells in
+    /// view for the next turn
+    pub fn forget(&mut self) {
+        for cell in Rc::make_mut(&mut self.cells).iter_mut() {
+            if *cell == OmmaVisibility::Visible {
+                *cell = OmmaVisibility::Remembered;
+            }
+        }
+    }
+}
+
+/// OmmaFilter transforms the cells a window and its descendants write into the back plane
+#[derive(Clone)]
+pub enum OmmaFilter {
+    /// Mixes fg and bg toward the color by an amount in 0.0..=1.0
+    Tint(OmmaColor, f64),
+    /// Multiplies fg and bg by the color channel by channel
+    Multiply(OmmaColor),
+    /// Mixes fg and bg toward their grey by an amount in 0.0..=1.0
+    Desaturate(f64),
+    /// Blanks hidden cells and dims remembered ones, the mask is laid over the content buffer
+    Visibility(OmmaMask),
+    /// Blends fg and bg with the cell already underneath, 0.0 keeps what is underneath
+    Alpha(f64),
+}
+
+impl OmmaFilter {
+    /// apply transforms cell about to be drawn over under, x and y are relative to the content
+    /// origin of the window holding the filter
+    pub fn apply(&self, cell: &OmmaCell, under: &OmmaCell, x: isize, y: isize) -> OmmaCell {
+        let recolor = |cell: &OmmaCell, f: &dyn Fn(&OmmaColor) -> OmmaColor| OmmaCell {
+            fg: cell.fg.as_ref().map(f),
+            bg: cell.bg.as_ref().map(f),
+            ..cell.clone()
+        };
+        match self {
+            OmmaFilter::Tint(color, amount) => recolor(cell, &|c| c.lerp(color, *amount)),
+            OmmaFilter::Multiply(color) => recolor(cell, &|c| c.multiply(color)),
+            OmmaFilter::Desaturate(amount) => recolor(cell, &|c| {
+                let luma = c.luma();
+                c.lerp(&OmmaColor::new(luma, luma, luma), *amount)
+            }),
+            OmmaFilter::Visibility(mask) => match mask.get(x, y) {
+                OmmaVisibility::Hidden => BLANK_CELL,
+                OmmaVisibility::Remembered => {
+                    let mut dimmed = recolor(cell, &|c| c.lerp(&BLACK, 0.5));
+                    dimmed.attrs |= ATTR_DIM;
+                    dimmed
+                }
+                OmmaVisibility::Visible => cell.clone(),
+            },
+            OmmaFilter::Alpha(alpha) => {
+                let alpha = alpha.clamp(0.0, 1.0);
+                let blend =
+                    |over: &Option<OmmaColor>, under: &Option<OmmaColor>| match (over, under) {
+                        (Some(over), Some(under)) => Some(under.lerp(over, alpha)),
+                        _ if alpha >= 0.5 => *over,
+                        _ => *under,
+                    };
+                let glyph = if alpha >= 0.5 { cell } else { under };
+                OmmaCell {
+                    ch: glyph.ch,
+                    fg: blend(&cell.fg, &under.fg),
+                    bg: blend(&cell.bg, &under.bg),
+                    attrs: glyph.attrs,
+                    tail: glyph.tail.clone(),
+                    role: glyph.role,
+                }
+            }
+        }
+    }
+}
+
+/// FilterFrame is the set of filters one window applies while it and its descendants are drawn
+pub(crate) struct FilterFrame {
+    pub(crate) filters: Vec<OmmaFilter>,
+    pub(crate) origin_x: isize,
+    pub(crate) origin_y: isize,
+}
+
+#[test]
+fn visibility() {
+    let mut mask = OmmaMask::new(2, 1, OmmaVisibility::Hidden);
+    mask.set(1, 0, OmmaVisibility::Visible).unwrap();
+    let filter = OmmaFilter::Visibility(mask.clone());
+    let wall = crate::cell::WALL_CELL;
+    assert!(filter.apply(&wall, &BLANK_CELL, 0, 0).ch == ' ');
+    assert!(filter.apply(&wall, &BLANK_CELL, 1, 0).ch == '#');
+    mask.forget();
+    let filter = OmmaFilter::Visibility(mask);
+    assert!(filter.apply(&wall, &BLANK_CELL, 1, 0).attrs & ATTR_DIM != 0);
+}
@@ -0,0 +1,233 @@
+use crate::cell::{DEFAULT_CELL, OmmaCell};
+use crate::color::{OmmaColor, OmmaGradient};
+use crate::error::OmmaErr;
+use crate::text::StyledText;
+use crate::theme::OmmaRole;
+use crate::window::Window;
+
+/// Left aligned eighth blocks, index n covers n+1 eighths of a cell
+const HORIZ_EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+/// Bottom aligned eighth blocks, index n covers n+1 eighths of a cell
+const VERT_EIGHTHS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GaugeOrientation {
+    /// Fills from left to right
+    Horizontal,
+    /// Fills from bottom to top
+    Vertical,
+}
+
+/// Gauge renders a fractional value as a bar with eighth of a cell precision
+#[derive(Clone)]
+pub struct Gauge {
+    value: f64,
+    orientation: GaugeOrientation,
+    fill: OmmaCell,
+    empty: OmmaCell,
+    label: Option<StyledText>,
+    label_cell: OmmaCell,
+    gradient: OmmaGradient,
+}
+
+impl Default for Gauge {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+impl Gauge {
+    /// new creates a horizontal gauge, value is clamped to 0.0..=1.0
+    pub fn new(value: f64) -> Self {
+        Gauge {
+            value: value.clamp(0.0, 1.0),
+            orientation: GaugeOrientation::Horizontal,
+            fill: OmmaCell {
+                ch: '█',
+                role: Some(OmmaRole::Accent),
+                ..DEFAULT_CELL
+            },
+            empty: DEFAULT_CELL,
+            label: None,
+            label_cell: DEFAULT_CELL,
+            gradient: OmmaGradient::new(),
+        }
+    }
+
+    /// ratio creates a horizontal gauge showing current out of max
+    pub fn ratio(current: u32, max: u32) -> Self {
+        if max == 0 {
+            Self::new(0.0)
+        } else {
+            Self::new(current as f64 / max as f64)
+        }
+    }
+
+    /// vertical makes the gauge fill from bottom to top
+    pub fn vertical(mut self) -> Self {
+        self.orientation = GaugeOrientation::Vertical;
+        self
+    }
+
+    /// fill sets the cell used for filled space, its fg also colors the partial cell
+    pub fn fill(mut self, cell: &OmmaCell) -> Self {
+        self.fill = cell.clone();
+        self
+    }
+
+    /// empty sets the cell used for unfilled space, its bg also backs the partial cell
+    pub fn empty(mut self, cell: &OmmaCell) -> Self {
+        self.empty = cell.clone();
+        self
+    }
+
+    /// label sets a text drawn centered over the gauge using the colors of label_cell
+    pub fn label(mut self, label: impl Into<StyledText>, label_cell: &OmmaCell) -> Self {
+        self.label = Some(label.into());
+        self.label_cell = label_cell.clone();
+        self
+    }
+
+    /// gradient colors the filled cells by their position along the gauge, interpolating between
+    /// evenly spaced stops. It replaces the fill cell's fg and its accent role.
+    pub fn gradient(mut self, stops: &[OmmaColor]) -> Self {
+        self.gradient = OmmaGradient::even(stops);
+        self
+    }
+
+    /// gradient_raw colors the filled cells by sampling gradient at their position along the gauge
+    pub fn gradient_raw(mut self, gradient: &OmmaGradient) -> Self {
+        self.gradient = gradient.clone();
+        self
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// set_value updates the gauge, value is clamped to 0.0..=1.0
+    pub fn set_value(&mut self, value: f64) {
+        self.value = value.clamp(0.0, 1.0);
+    }
+
+    pub fn clear_label(&mut self) {
+        self.label = None;
+    }
+
+    pub fn set_label(&mut self, label: impl Into<StyledText>) {
+        self.label = Some(label.into());
+    }
+
+    /// split returns the number of full cells and the eighths of the partial cell for a gauge
+    /// len cells long
+    fn split(&self, len: usize) -> (usize, usize) {
+        let eighths = (self.value * (len * 8) as f64).round() as usize;
+        (eighths / 8, eighths % 8)
+    }
+
+    /// cells lays the bar out along len cells, starting at the top of a vertical gauge and at the
+    /// left of a horizontal one. The label is not included.
+    pub fn cells(&self, len: usize) -> Vec<OmmaCell> {
+        let (full, partial) = self.split(len);
+        let blocks = match self.orientation {
+            GaugeOrientation::Horizontal => &HORIZ_EIGHTHS,
+            GaugeOrientation::Vertical => &VERT_EIGHTHS,
+        };
+
+        let mut cells: Vec<OmmaCell> = (0..len)
+            .map(|index| {
+                let position = if len > 1 {
+                    index as f64 / (len - 1) as f64
+                } else {
+                    0.0
+                };
+                let mut fill = self.fill.clone();
+                if let Some(color) = self.gradient.sample(position) {
+                    // The gradient wins over the theme
+                    fill.fg = Some(color);
+                    fill.role = None;
+                }
+                if index < full {
+                    fill
+                } else if index == full && partial > 0 {
+                    OmmaCell {
+                        ch: blocks[partial - 1],
+                        bg: self.empty.bg,
+                        ..fill
+                    }
+                } else {
+                    self.empty.clone()
+                }
+            })
+            .collect();
+
+        if self.orientation == GaugeOrientation::Vertical {
+            cells.reverse();
+        }
+        cells
+    }
+
+    /// draw fills the content area of a window with the gauge, returns the number of cells written
+    pub fn draw(&self, window: &mut Window) -> Result<u32, OmmaErr> {
+        let cols = window.content_width();
+        let rows = window.content_height();
+        let mut written = 0;
+        let (len, filled_at): (usize, fn(usize, usize, usize) -> usize) = match self.orientation {
+            GaugeOrientation::Horizontal => (cols, |x, _, _| x),
+            GaugeOrientation::Vertical => (rows, |_, y, rows| rows - 1 - y),
+        };
+        let bar = self.cells(len);
+        for x in 0..cols {
+            for y in 0..rows {
+                let cell = match self.orientation {
+                    GaugeOrientation::Horizontal => &bar[x],
+                    GaugeOrientation::Vertical => &bar[y],
+                };
+                window.set_ommacell(x, y, cell)?;
+                written += 1;
+            }
+        }
+
+        if let Some(label) = &self.label
+            && rows > 0
+        {
+            let (full, _) = self.split(len);
+            // Laid out without a background so the only one left is what a span set itself
+            let base = OmmaCell {
+                bg: None,
+                ..self.label_cell.clone()
+            };
+            let cells = label.clip_cells(&base, cols);
+            let y = rows / 2;
+            let start = (cols - cells.len()) / 2;
+            for (x, cell) in (start..).zip(cells) {
+                // Labels sit on the bar's color where filled and the empty color elsewhere,
+                // unless a styled span brings its own background
+                let bg = if cell.bg.is_some() {
+                    cell.bg
+                } else if filled_at(x, y, rows) < full {
+                    window.get_ommacell(x, y)?.fg
+                } else {
+                    self.empty.bg
+                };
+                window.set_ommacell(x, y, &OmmaCell { bg, ..cell })?;
+            }
+        }
+        Ok(written)
+    }
+}
+
+#[test]
+fn cells() {
+    // 17 of 32 eighths is two full cells and one eighth
+    let gauge = Gauge::new(17.0 / 32.0);
+    let chars: String = gauge.cells(4).iter().map(|cell| cell.ch).collect();
+    assert!(chars == "██▏ ");
+    let chars: String = gauge
+        .vertical()
+        .cells(4)
+        .iter()
+        .map(|cell| cell.ch)
+        .collect();
+    assert!(chars == " ▁██");
+}
@@ -0,0 +1,397 @@
+//! Images are read from uncompressed PPM and PGM files, plain or binary, and from BMP files with
+//! 1, 4, 8, 24 or 32 bits per pixel. Each cell shows two pixels stacked in a half block.
+
+use crate::cell::OmmaCell;
+use crate::color::OmmaColor;
+use crate::error::OmmaErr;
+use crate::pixel::{OmmaPixels, PixelMode};
+use crate::window::Window;
+use std::path::Path;
+
+/// Dither picks how colors missing from a palette are approximated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dither {
+    /// Each pixel takes the nearest palette color
+    #[default]
+    None,
+    /// The error of each pixel is spread over its neighbours, trading banding for noise
+    FloydSteinberg,
+}
+
+/// OmmaImage is a grid of opaque truecolor pixels
+#[derive(Clone, PartialEq)]
+pub struct OmmaImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<OmmaColor>,
+}
+
+impl OmmaImage {
+    /// new creates a width by height image filled with color
+    pub fn new(width: usize, height: usize, color: &OmmaColor) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![*color; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<OmmaColor> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.pixels[y * self.width + x])
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: &OmmaColor) -> Result<(), OmmaErr> {
+        if x >= self.width || y >= self.height {
+            return Err(OmmaErr::new(&format!(
+                "invalid image write to {}:{} (max {}:{})",
+                x, y, self.width, self.height
+            )));
+        }
+        self.pixels[y * self.width + x] = *color;
+        Ok(())
+    }
+
+    /// parse reads a PPM, PGM or BMP image from its bytes, telling the format from its header
+    pub fn parse(bytes: &[u8]) -> Result<Self, OmmaErr> {
+        match bytes {
+            [b'P', b'2' | b'3' | b'5' | b'6', ..] => Self::parse_pnm(bytes),
+            [b'B', b'M', ..] => Self::parse_bmp(bytes),
+            _ => Err(OmmaErr::new("image is not a PPM, PGM or BMP file")),
+        }
+    }
+
+    /// load reads an image file, see parse
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, OmmaErr> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    fn parse_pnm(bytes: &[u8]) -> Result<Self, OmmaErr> {
+        let invalid = |reason: &str| OmmaErr::new(&format!("invalid PPM or PGM image: {}", reason));
+        let kind = bytes[1];
+        let channels = if kind == b'2' || kind == b'5' { 1 } else { 3 };
+
+        // The header is the magic number, width, height and maxval separated by whitespace and
+        // comments, followed by a single whitespace byte before binary samples
+        let mut pos = 2;
+        let mut token = || -> Result<usize, OmmaErr> {
+            loop {
+                match bytes.get(pos) {
+                    Some(b'#') => {
+                        while bytes.get(pos).is_some_and(|byte| *byte != b'\n') {
+                            pos += 1;
+                        }
+                    }
+                    Some(byte) if byte.is_ascii_whitespace() => pos += 1,
+                    _ => break,
+                }
+            }
+            let start = pos;
+            while bytes.get(pos).is_some_and(|byte| byte.is_ascii_digit()) {
+                pos += 1;
+            }
+            std::str::from_utf8(&bytes[start..pos])
+                .ok()
+                .and_then(|digits| digits.parse().ok())
+                .ok_or_else(|| invalid("expected a number"))
+        };
+        let width = token()?;
+        let height = token()?;
+        let maxval = token()?;
+        if maxval == 0 || maxval > 65535 {
+            return Err(invalid("maxval must be 1 to 65535"));
+        }
+
+        let count = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(channels))
+            .ok_or_else(|| invalid("image too large"))?;
+        let samples: Vec<usize> = if kind == b'2' || kind == b'3' {
+            (0..count).map(|_| token()).collect::<Result<_, _>>()?
+        } else {
+            let wide = maxval > 255;
+            let start = pos + 1;
+            let size = if wide { 2 } else { 1 };
+            let end = count
+                .checked_mul(size)
+                .and_then(|len| len.checked_add(start))
+                .ok_or_else(|| invalid("image too large"))?;
+            let data = bytes
+                .get(start..end)
+                .ok_or_else(|| invalid("pixel data is cut short"))?;
+            data.chunks(size)
+                .map(|sample| match sample {
+                    [high, low] => (*high as usize) << 8 | *low as usize,
+                    [byte] => *byte as usize,
+                    _ => 0,
+                })
+                .collect()
+        };
+        if samples.iter().any(|sample| *sample > maxval) {
+            return Err(invalid("sample larger than maxval"));
+        }
+
+        let scale = |sample: usize| ((sample * 255 + maxval / 2) / maxval) as u8;
+        let pixels = samples
+            .chunks(channels)
+            .map(|pixel| match pixel {
+                [r, g, b] => OmmaColor::new(scale(*r), scale(*g), scale(*b)),
+                [grey] => OmmaColor::new(scale(*grey), scale(*grey), scale(*grey)),
+                _ => OmmaColor::new(0, 0, 0),
+            })
+            .collect();
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    fn parse_bmp(bytes: &[u8]) -> Result<Self, OmmaErr> {
+        let invalid = |reason: &str| OmmaErr::new(&format!("invalid BMP image: {}", reason));
+        let u16_at = |at: usize| {
+            bytes
+                .get(at..at + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .ok_or_else(|| invalid("header is cut short"))
+        };
+        let u32_at = |at: usize| {
+            bytes
+                .get(at..at + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or_else(|| invalid("header is cut short"))
+        };
+
+        let data = u32_at(10)? as usize;
+        let header = u32_at(14)? as usize;
+        let width = u32_at(18)? as i32;
+        let height = u32_at(22)? as i32;
+        let bits = u16_at(28)? as usize;
+        let compression = u32_at(30)?;
+        if width <= 0 || height == 0 {
+            return Err(invalid("empty image"));
+        }
+        if !matches!(bits, 1 | 4 | 8 | 24 | 32) {
+            return Err(invalid(&format!(
+                "{} bits per pixel is not supported",
+                bits
+            )));
+        }
+        // Compression 3 only describes channel masks, 32 bit images use the usual BGRA order
+        if compression != 0 && !(compression == 3 && bits == 32) {
+            return Err(invalid("compressed images are not supported"));
+        }
+
+        let palette: Vec<OmmaColor> = if bits <= 8 {
+            let used = u32_at(46)? as usize;
+            let count = if used == 0 {
+                1 << bits
+            } else {
+                used.min(1 << bits)
+            };
+            let start = 14 + header;
+            bytes
+                .get(start..start + count * 4)
+                .ok_or_else(|| invalid("palette is cut short"))?
+                .chunks(4)
+                .map(|bgrx| OmmaColor::new(bgrx[2], bgrx[1], bgrx[0]))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Rows are padded to 4 bytes and stored bottom up unless the height is negative
+        let (width, top_down) = (width as usize, height < 0);
+        let height = height.unsigned_abs() as usize;
+        let stride = bits
+            .checked_mul(width)
+            .map(|row_bits| row_bits.div_ceil(32) * 4)
+            .ok_or_else(|| invalid("image too large"))?;
+        let end = stride
+            .checked_mul(height)
+            .and_then(|len| len.checked_add(data))
+            .ok_or_else(|| invalid("image too large"))?;
+        let rows = bytes
+            .get(data..end)
+            .ok_or_else(|| invalid("pixel data is cut short"))?;
+        let mut image = Self::new(width, height, &OmmaColor::new(0, 0, 0));
+        for (row, line) in rows.chunks(stride).enumerate() {
+            let y = if top_down { row } else { height - 1 - row };
+            for x in 0..width {
+                let color = match bits {
+                    24 | 32 => {
+                        let at = x * bits / 8;
+                        OmmaColor::new(line[at + 2], line[at + 1], line[at])
+                    }
+                    _ => {
+                        let bit = x * bits;
+                        let index =
+                            (line[bit / 8] as usize >> (8 - bits - bit % 8)) & ((1 << bits) - 1);
+                        *palette
+                            .get(index)
+                            .ok_or_else(|| invalid("palette index out of range"))?
+                    }
+                };
+                image.pixels[y * width + x] = color;
+            }
+        }
+        Ok(image)
+    }
+
+    /// scale resizes the image to width by height, picking the nearest pixel
+    pub fn scale(&self, width: usize, height: usize) -> Self {
+        let mut scaled = Self::new(width, height, &OmmaColor::new(0, 0, 0));
+        for y in 0..height {
+            for x in 0..width {
+                let source_x = x * self.width / width.max(1);
+                let source_y = y * self.height / height.max(1);
+                if let Some(color) = self.get(source_x, source_y) {
+                    scaled.pixels[y * width + x] = color;
+                }
+            }
+        }
+        scaled
+    }
+
+    /// quantize redraws the image in the colors of palette for terminals with reduced colors
+    pub fn quantize(&self, palette: &[OmmaColor], dither: Dither) -> Self {
+        if palette.is_empty() {
+            return self.clone();
+        }
+        let nearest = |rgb: [f64; 3]| -> OmmaColor {
+            let distance = |color: &OmmaColor| {
+                let d = |channel: u8, value: f64| (channel as f64 - value).powi(2);
+                d(color.r, rgb[0]) + d(color.g, rgb[1]) + d(color.b, rgb[2])
+            };
+            *palette
+                .iter()
+                .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+                .unwrap_or(&palette[0])
+        };
+        let mut error = vec![[0.0; 3]; self.pixels.len()];
+        let mut quantized = self.clone();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = y * self.width + x;
+                let color = self.pixels[index];
+                let rgb = [
+                    (color.r as f64 + error[index][0]).clamp(0.0, 255.0),
+                    (color.g as f64 + error[index][1]).clamp(0.0, 255.0),
+                    (color.b as f64 + error[index][2]).clamp(0.0, 255.0),
+                ];
+                let chosen = nearest(rgb);
+                quantized.pixels[index] = chosen;
+                if dither == Dither::None {
+                    continue;
+                }
+                let diff = [
+                    rgb[0] - chosen.r as f64,
+                    rgb[1] - chosen.g as f64,
+                    rgb[2] - chosen.b as f64,
+                ];
+                let spread = [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)];
+                for (dx, dy, weight) in spread {
+                    let (nx, ny) = (x as isize + dx, y + dy);
+                    if nx < 0 || nx as usize >= self.width || ny >= self.height {
+                        continue;
+                    }
+                    let neighbour = &mut error[ny * self.width + nx as usize];
+                    for (channel, diff) in neighbour.iter_mut().zip(diff) {
+                        *channel += diff * weight / 16.0;
+                    }
+                }
+            }
+        }
+        quantized
+    }
+
+    /// pixels lays the image out on a half block pixel canvas, one cell for every two rows
+    pub fn pixels(&self) -> OmmaPixels {
+        let mut pixels = OmmaPixels::new(PixelMode::HalfBlock, self.width, self.height.div_ceil(2));
+        for y in 0..self.height {
+            for x in 0..self.width {
+                pixels.plot(x as isize, y as isize, &self.pixels[y * self.width + x]);
+            }
+        }
+        pixels
+    }
+
+    /// draw writes the image into window as half blocks with its top left corner at x, y. An odd
+    /// last row shows base's bg beneath it.
+    pub fn draw(
+        &self,
+        window: &mut Window,
+        x: usize,
+        y: usize,
+        base: &OmmaCell,
+    ) -> Result<u32, OmmaErr> {
+        self.pixels().draw(window, x, y, base)
+    }
+}
+
+/// ansi_palette returns the 16 colors of the basic and bright ANSI palette as xterm draws them
+pub fn ansi_palette() -> Vec<OmmaColor> {
+    [
+        0x000000, 0xcd0000, 0x00cd00, 0xcdcd00, 0x0000ee, 0xcd00cd, 0x00cdcd, 0xe5e5e5, 0x7f7f7f,
+        0xff0000, 0x00ff00, 0xffff00, 0x5c5cff, 0xff00ff, 0x00ffff, 0xffffff,
+    ]
+    .into_iter()
+    .map(|rgb: u32| OmmaColor::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+    .collect()
+}
+
+/// xterm_palette returns the 256 color palette, the ANSI colors followed by a 6x6x6 color cube and
+/// 24 greys
+pub fn xterm_palette() -> Vec<OmmaColor> {
+    let levels = [0, 95, 135, 175, 215, 255];
+    let mut palette = ansi_palette();
+    for r in levels {
+        for g in levels {
+            for b in levels {
+                palette.push(OmmaColor::new(r, g, b));
+            }
+        }
+    }
+    palette.extend((0..24).map(|i| OmmaColor::new(8 + i * 10, 8 + i * 10, 8 + i * 10)));
+    palette
+}
+
+/// grey_palette returns levels greys evenly spaced from black to white
+pub fn grey_palette(levels: usize) -> Vec<OmmaColor> {
+    let steps = levels.saturating_sub(1).max(1);
+    (0..levels)
+        .map(|i| {
+            let value = ((i * 255 + steps / 2) / steps) as u8;
+            OmmaColor::new(value, value, value)
+        })
+        .collect()
+}
+
+#[test]
+fn parse() {
+    use crate::color::{BLACK, WHITE};
+
+    let plain = OmmaImage::parse(b"P3\n# two pixels\n2 1\n15\n15 0 0  0 0 15\n").unwrap();
+    assert!(plain.width() == 2 && plain.height() == 1);
+    assert!(plain.get(0, 0) == Some(OmmaColor::new(255, 0, 0)));
+    assert!(plain.get(1, 0) == Some(OmmaColor::new(0, 0, 255)));
+
+    let grey = OmmaImage::parse(b"P5 1 2 255\n\x00\xff").unwrap();
+    assert!(grey.get(0, 0) == Some(BLACK) && grey.get(0, 1) == Some(WHITE));
+    assert!(OmmaImage::parse(b"P6 4 4 255\n\x00").is_err());
+    assert!(OmmaImage::parse(b"P2 1 1 3\n9\n").is_err());
+    assert!(OmmaImage::parse(b"GIF89a").is_err());
+
+    assert!(xterm_palette().len() == 256);
+    assert!(grey_palette(3)[1] == OmmaColor::new(128, 128, 128));
+}
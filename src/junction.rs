@@ -0,0 +1,220 @@
+//! Box drawing junctions. Every box drawing glyph is described by the line style of its four arms,
+//! two glyphs meeting in a cell are merged by combining their arms and looking the result back up.
+
+const NONE: u8 = 0;
+const LIGHT: u8 = 1;
+
+/// Arms are the line styles reaching up, down, left and right out of a cell: none, light, heavy,
+/// double or ASCII
+type Arms = [u8; 4];
+
+/// GLYPHS lists box drawing characters with their arms. Lookups take the first match so the plain
+/// solid glyphs come before rounded and dashed variants that share arms.
+const GLYPHS: [(char, Arms); 128] = [
+    ('─', [0, 0, 1, 1]),
+    ('━', [0, 0, 2, 2]),
+    ('│', [1, 1, 0, 0]),
+    ('┃', [2, 2, 0, 0]),
+    ('┌', [0, 1, 0, 1]),
+    ('┍', [0, 1, 0, 2]),
+    ('┎', [0, 2, 0, 1]),
+    ('┏', [0, 2, 0, 2]),
+    ('┐', [0, 1, 1, 0]),
+    ('┑', [0, 1, 2, 0]),
+    ('┒', [0, 2, 1, 0]),
+    ('┓', [0, 2, 2, 0]),
+    ('└', [1, 0, 0, 1]),
+    ('┕', [1, 0, 0, 2]),
+    ('┖', [2, 0, 0, 1]),
+    ('┗', [2, 0, 0, 2]),
+    ('┘', [1, 0, 1, 0]),
+    ('┙', [1, 0, 2, 0]),
+    ('┚', [2, 0, 1, 0]),
+    ('┛', [2, 0, 2, 0]),
+    ('├', [1, 1, 0, 1]),
+    ('┝', [1, 1, 0, 2]),
+    ('┞', [2, 1, 0, 1]),
+    ('┟', [1, 2, 0, 1]),
+    ('┠', [2, 2, 0, 1]),
+    ('┡', [2, 1, 0, 2]),
+    ('┢', [1, 2, 0, 2]),
+    ('┣', [2, 2, 0, 2]),
+    ('┤', [1, 1, 1, 0]),
+    ('┥', [1, 1, 2, 0]),
+    ('┦', [2, 1, 1, 0]),
+    ('┧', [1, 2, 1, 0]),
+    ('┨', [2, 2, 1, 0]),
+    ('┩', [2, 1, 2, 0]),
+    ('┪', [1, 2, 2, 0]),
+    ('┫', [2, 2, 2, 0]),
+    ('┬', [0, 1, 1, 1]),
+    ('┭', [0, 1, 2, 1]),
+    ('┮', [0, 1, 1, 2]),
+    ('┯', [0, 1, 2, 2]),
+    ('┰', [0, 2, 1, 1]),
+    ('┱', [0, 2, 2, 1]),
+    ('┲', [0, 2, 1, 2]),
+    ('┳', [0, 2, 2, 2]),
+    ('┴', [1, 0, 1, 1]),
+    ('┵', [1, 0, 2, 1]),
+    ('┶', [1, 0, 1, 2]),
+    ('┷', [1, 0, 2, 2]),
+    ('┸', [2, 0, 1, 1]),
+    ('┹', [2, 0, 2, 1]),
+    ('┺', [2, 0, 1, 2]),
+    ('┻', [2, 0, 2, 2]),
+    ('┼', [1, 1, 1, 1]),
+    ('┽', [1, 1, 2, 1]),
+    ('┾', [1, 1, 1, 2]),
+    ('┿', [1, 1, 2, 2]),
+    ('╀', [2, 1, 1, 1]),
+    ('╁', [1, 2, 1, 1]),
+    ('╂', [2, 2, 1, 1]),
+    ('╃', [2, 1, 2, 1]),
+    ('╄', [2, 1, 1, 2]),
+    ('╅', [1, 2, 2, 1]),
+    ('╆', [1, 2, 1, 2]),
+    ('╇', [2, 1, 2, 2]),
+    ('╈', [1, 2, 2, 2]),
+    ('╉', [2, 2, 2, 1]),
+    ('╊', [2, 2, 1, 2]),
+    ('╋', [2, 2, 2, 2]),
+    ('═', [0, 0, 3, 3]),
+    ('║', [3, 3, 0, 0]),
+    ('╒', [0, 1, 0, 3]),
+    ('╓', [0, 3, 0, 1]),
+    ('╔', [0, 3, 0, 3]),
+    ('╕', [0, 1, 3, 0]),
+    ('╖', [0, 3, 1, 0]),
+    ('╗', [0, 3, 3, 0]),
+    ('╘', [1, 0, 0, 3]),
+    ('╙', [3, 0, 0, 1]),
+    ('╚', [3, 0, 0, 3]),
+    ('╛', [1, 0, 3, 0]),
+    ('╜', [3, 0, 1, 0]),
+    ('╝', [3, 0, 3, 0]),
+    ('╞', [1, 1, 0, 3]),
+    ('╟', [3, 3, 0, 1]),
+    ('╠', [3, 3, 0, 3]),
+    ('╡', [1, 1, 3, 0]),
+    ('╢', [3, 3, 1, 0]),
+    ('╣', [3, 3, 3, 0]),
+    ('╤', [0, 1, 3, 3]),
+    ('╥', [0, 3, 1, 1]),
+    ('╦', [0, 3, 3, 3]),
+    ('╧', [1, 0, 3, 3]),
+    ('╨', [3, 0, 1, 1]),
+    ('╩', [3, 0, 3, 3]),
+    ('╪', [1, 1, 3, 3]),
+    ('╫', [3, 3, 1, 1]),
+    ('╬', [3, 3, 3, 3]),
+    ('╴', [0, 0, 1, 0]),
+    ('╵', [1, 0, 0, 0]),
+    ('╶', [0, 0, 0, 1]),
+    ('╷', [0, 1, 0, 0]),
+    ('╸', [0, 0, 2, 0]),
+    ('╹', [2, 0, 0, 0]),
+    ('╺', [0, 0, 0, 2]),
+    ('╻', [0, 2, 0, 0]),
+    ('╼', [0, 0, 1, 2]),
+    ('╽', [1, 2, 0, 0]),
+    ('╾', [0, 0, 2, 1]),
+    ('╿', [2, 1, 0, 0]),
+    ('╭', [0, 1, 0, 1]),
+    ('╮', [0, 1, 1, 0]),
+    ('╯', [1, 0, 1, 0]),
+    ('╰', [1, 0, 0, 1]),
+    ('┄', [0, 0, 1, 1]),
+    ('┈', [0, 0, 1, 1]),
+    ('╌', [0, 0, 1, 1]),
+    ('┅', [0, 0, 2, 2]),
+    ('┉', [0, 0, 2, 2]),
+    ('╍', [0, 0, 2, 2]),
+    ('┆', [1, 1, 0, 0]),
+    ('┊', [1, 1, 0, 0]),
+    ('╎', [1, 1, 0, 0]),
+    ('┇', [2, 2, 0, 0]),
+    ('┋', [2, 2, 0, 0]),
+    ('╏', [2, 2, 0, 0]),
+    ('-', [0, 0, 4, 4]),
+    ('|', [4, 4, 0, 0]),
+    ('+', [4, 4, 4, 4]),
+];
+
+/// ASCII arms only ever merge into one of -, | or +
+const ASCII: u8 = 4;
+
+fn arms(ch: char) -> Option<Arms> {
+    GLYPHS
+        .iter()
+        .find(|(glyph, _)| *glyph == ch)
+        .map(|(_, arms)| *arms)
+}
+
+fn glyph(arms: Arms) -> Option<char> {
+    GLYPHS
+        .iter()
+        .find(|(_, glyph_arms)| *glyph_arms == arms)
+        .map(|(glyph, _)| *glyph)
+}
+
+/// is_box returns true if ch is a glyph that can take part in a junction
+pub fn is_box(ch: char) -> bool {
+    arms(ch).is_some()
+}
+
+/// merge returns the junction glyph for over drawn on top of under, or None when either is not a
+/// box drawing glyph. Arms present in both keep the style of over.
+pub fn merge(under: char, over: char) -> Option<char> {
+    let under = arms(under)?;
+    let over = arms(over)?;
+
+    if under.contains(&ASCII) || over.contains(&ASCII) {
+        let vert = under[0] | under[1] | over[0] | over[1] != NONE;
+        let horiz = under[2] | under[3] | over[2] | over[3] != NONE;
+        return Some(match (vert, horiz) {
+            (true, false) => '|',
+            (false, true) => '-',
+            _ => '+',
+        });
+    }
+
+    let mut merged = under;
+    for (arm, style) in merged.iter_mut().zip(over) {
+        if style != NONE {
+            *arm = style;
+        }
+    }
+    if let Some(ch) = glyph(merged) {
+        return Some(ch);
+    }
+
+    // Unicode only mixes double lines with light ones a whole axis at a time, so settle each
+    // axis on its strongest style before giving up and drawing everything light
+    let mut axes = merged;
+    for axis in [0, 2] {
+        let style = axes[axis].max(axes[axis + 1]);
+        for arm in &mut axes[axis..axis + 2] {
+            if *arm != NONE {
+                *arm = style;
+            }
+        }
+    }
+    if let Some(ch) = glyph(axes) {
+        return Some(ch);
+    }
+    let light = merged.map(|style| if style == NONE { NONE } else { LIGHT });
+    glyph(light)
+}
+
+#[test]
+fn merge_junctions() {
+    assert!(merge('┐', '┌') == Some('┬'));
+    assert!(merge('│', '─') == Some('┼'));
+    assert!(merge('╗', '╔') == Some('╦'));
+    assert!(merge('║', '─') == Some('╫'));
+    assert!(merge('╭', '┘') == Some('┼'));
+    assert!(merge('┃', '═') == Some('┼'));
+    assert!(merge('+', '|') == Some('+'));
+    assert!(merge('#', '─').is_none());
+}
@@ -0,0 +1,106 @@
+//! Default key bindings shared by the interactive widgets. Keys arrive one char at a time from
+//! Session::read_key. Arrow keys send escape sequences, which are decoded into the private use
+//! KEY_ARROW chars below so a lone escape still reads as KEY_ESCAPE. Session::read_input also
+//! returns mouse presses once Session::enable_mouse has turned on mouse reporting.
+
+pub const KEY_UP: char = 'k';
+pub const KEY_DOWN: char = 'j';
+pub const KEY_LEFT: char = 'h';
+pub const KEY_RIGHT: char = 'l';
+pub const KEY_TAB: char = '\t';
+pub const KEY_ENTER: char = '\r';
+pub const KEY_SPACE: char = ' ';
+pub const KEY_ESCAPE: char = '\x1b';
+pub const KEY_ARROW_UP: char = '\u{F700}';
+pub const KEY_ARROW_DOWN: char = '\u{F701}';
+pub const KEY_ARROW_LEFT: char = '\u{F702}';
+pub const KEY_ARROW_RIGHT: char = '\u{F703}';
+
+/// OmmaInput is a keypress or a mouse press read from the terminal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OmmaInput {
+    Key(char),
+    /// A left button press at a terminal column and row
+    Click(usize, usize),
+}
+
+/// decode_input turns the bytes of one read from the terminal into input. CSI and SS3 arrow
+/// sequences become KEY_ARROW chars and SGR mouse reports of a left button press become clicks.
+/// Other escape sequences are dropped and an escape that starts no sequence is KEY_ESCAPE.
+pub(crate) fn decode_input(bytes: &[u8]) -> Vec<OmmaInput> {
+    let mut keys = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        index += 1;
+        if byte != KEY_ESCAPE as u8 {
+            keys.push(OmmaInput::Key(byte as char));
+            continue;
+        }
+        match bytes.get(index) {
+            Some(b'[') => {
+                // Parameter and intermediate bytes run up to a final byte in 0x40..=0x7e
+                index += 1;
+                let start = index;
+                while index < bytes.len() && !(0x40..=0x7e).contains(&bytes[index]) {
+                    index += 1;
+                }
+                match bytes.get(index) {
+                    Some(b'M') => keys.extend(click(&bytes[start..index])),
+                    Some(&end) => keys.extend(arrow(end).map(OmmaInput::Key)),
+                    None => (),
+                }
+                index += 1;
+            }
+            Some(b'O') if bytes.get(index + 1).is_some() => {
+                if let Some(key) = arrow(bytes[index + 1]) {
+                    keys.push(OmmaInput::Key(key));
+                }
+                index += 2;
+            }
+            _ => keys.push(OmmaInput::Key(KEY_ESCAPE)),
+        }
+    }
+    keys
+}
+
+/// click reads the parameters of an SGR mouse report, "<button;column;row" counted from 1. Only
+/// presses of the left button count, drags, wheel turns and other buttons are dropped.
+fn click(params: &[u8]) -> Option<OmmaInput> {
+    let params = std::str::from_utf8(params.strip_prefix(b"<")?).ok()?;
+    let mut fields = params.split(';').map(|field| field.parse::<usize>().ok());
+    let (button, x, y) = (fields.next()??, fields.next()??, fields.next()??);
+    if button & 0b1110_0011 != 0 {
+        return None;
+    }
+    Some(OmmaInput::Click(x.checked_sub(1)?, y.checked_sub(1)?))
+}
+
+fn arrow(end: u8) -> Option<char> {
+    match end {
+        b'A' => Some(KEY_ARROW_UP),
+        b'B' => Some(KEY_ARROW_DOWN),
+        b'C' => Some(KEY_ARROW_RIGHT),
+        b'D' => Some(KEY_ARROW_LEFT),
+        _ => None,
+    }
+}
+
+#[test]
+fn escapes() {
+    assert!(decode_input(b"\x1b") == vec![OmmaInput::Key(KEY_ESCAPE)]);
+    assert!(decode_input(b"\x1b[A") == vec![OmmaInput::Key(KEY_ARROW_UP)]);
+    assert!(decode_input(b"\x1bOD") == vec![OmmaInput::Key(KEY_ARROW_LEFT)]);
+    let right = vec![OmmaInput::Key(KEY_ARROW_RIGHT), OmmaInput::Key('x')];
+    assert!(decode_input(b"\x1b[1;5Cx") == right);
+    // Unknown sequences are dropped rather than leaking their bytes as keys
+    assert!(decode_input(b"\x1b[3~j") == vec![OmmaInput::Key(KEY_DOWN)]);
+}
+
+#[test]
+fn mouse() {
+    assert!(decode_input(b"\x1b[<0;3;1M") == vec![OmmaInput::Click(2, 0)]);
+    // Releases, drags, the wheel and other buttons are dropped
+    let ignored = b"\x1b[<0;3;1m\x1b[<32;4;1M\x1b[<64;3;1M\x1b[<2;3;1Mq";
+    assert!(decode_input(ignored) == vec![OmmaInput::Key('q')]);
+}
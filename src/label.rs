@@ -0,0 +1,133 @@
+use crate::cell::{DEFAULT_CELL, EMPTY_CELL, OmmaCell};
+use crate::text::StyledText;
+use crate::theme::OmmaRole;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OmmaAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// OmmaLabel is a line of text drawn into a window's top or bottom border, styled text spans are
+/// drawn over the label's cell
+#[derive(Clone)]
+#[allow(dead_code)]
+pub struct OmmaLabel {
+    text: StyledText,
+    align: OmmaAlign,
+    cell: OmmaCell,
+    decor: Option<(OmmaCell, OmmaCell)>,
+}
+
+impl Default for OmmaLabel {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl OmmaLabel {
+    pub fn new(text: impl Into<StyledText>) -> Self {
+        OmmaLabel {
+            text: text.into(),
+            align: OmmaAlign::Left,
+            cell: OmmaCell {
+                role: Some(OmmaRole::Title),
+                ..DEFAULT_CELL
+            },
+            decor: None,
+        }
+    }
+
+    /// align sets where the label sits along the border
+    pub fn align(mut self, align: OmmaAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// cell sets the style of the label text, labels are drawn in the title role by default
+    pub fn cell(mut self, cell: &OmmaCell) -> Self {
+        self.cell = cell.clone();
+        self
+    }
+
+    /// decor wraps the label in a pair of cells, such as ╡ and ╞
+    pub fn decor(mut self, left: &OmmaCell, right: &OmmaCell) -> Self {
+        self.decor = Some((left.clone(), right.clone()));
+        self
+    }
+
+    // Getters
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+    pub fn styled_text(&self) -> &StyledText {
+        &self.text
+    }
+    pub fn alignment(&self) -> OmmaAlign {
+        self.align
+    }
+
+    // Setters
+    pub fn set_text(&mut self, text: impl Into<StyledText>) {
+        self.text = text.into();
+    }
+
+    /// cells lays the label out along a border span len cells long. Cells outside the label are
+    /// EMPTY so the border shows through. Text that does not fit is cut short with an ellipsis.
+    pub fn cells(&self, len: usize) -> Vec<OmmaCell> {
+        let mut strip = vec![EMPTY_CELL; len];
+        // Keep a cell of border between the label and the corners when aligned to an edge
+        let margin = if self.align == OmmaAlign::Center || len < 3 {
+            0
+        } else {
+            1
+        };
+        let avail = len - 2 * margin;
+        let decor = self.decor.as_ref().filter(|_| avail >= 3);
+        let room = avail - if decor.is_some() { 2 } else { 0 };
+
+        let mut label = self.text.fit_cells(&self.cell, room);
+        if let Some((left, right)) = decor {
+            label.insert(0, left.clone());
+            label.push(right.clone());
+        }
+
+        let start = match self.align {
+            OmmaAlign::Left => margin,
+            OmmaAlign::Center => (len - label.len()) / 2,
+            OmmaAlign::Right => len - margin - label.len(),
+        };
+        for (index, cell) in (start..).zip(label) {
+            strip[index] = cell;
+        }
+        strip
+    }
+}
+
+#[test]
+fn cells() {
+    let decor_l = OmmaCell {
+        ch: '╡',
+        ..DEFAULT_CELL
+    };
+    let decor_r = OmmaCell {
+        ch: '╞',
+        ..DEFAULT_CELL
+    };
+    let label = OmmaLabel::new("Inventory").decor(&decor_l, &decor_r);
+    let chars: String = label
+        .cells(9)
+        .iter()
+        .map(|cell| if cell.ch == '\0' { '=' } else { cell.ch })
+        .collect();
+    assert!(chars == "=╡Inve…╞=");
+    let label = label.align(OmmaAlign::Right);
+    let chars: String = label
+        .cells(14)
+        .iter()
+        .map(|cell| if cell.ch == '\0' { '=' } else { cell.ch })
+        .collect();
+    assert!(chars == "==╡Inventory╞=");
+}
@@ -1,11 +1,34 @@
+pub mod banner;
 pub mod border;
+pub mod canvas;
 pub mod cell;
+pub mod clock;
 pub mod color;
+pub mod effect;
 pub mod error;
+pub mod filter;
+pub mod gauge;
+pub mod image;
+pub mod junction;
+pub mod key;
+pub mod label;
+pub mod menu;
 pub mod object;
 pub mod pad;
+pub mod particle;
+pub mod pixel;
+pub mod scrollbar;
 pub mod session;
+pub mod shader;
+pub mod shadow;
+pub mod sprite;
+pub mod table;
+pub mod tabs;
 pub mod term;
+pub mod text;
+pub mod theme;
+pub mod tween;
+pub mod unicode;
 pub mod window;
 
 use crate::error::OmmaErr;
@@ -13,6 +36,7 @@ use std::sync::atomic::{AtomicU32, Ordering};
 
 static WINDOW_ID: AtomicU32 = AtomicU32::new(0);
 static OBJECT_ID: AtomicU32 = AtomicU32::new(0);
+static EFFECT_ID: AtomicU32 = AtomicU32::new(0);
 
 pub fn next_window_id() -> Result<u32, OmmaErr> {
     let current_id = WINDOW_ID.load(Ordering::Relaxed);
@@ -43,3 +67,14 @@ pub fn next_object_id() -> Result<u32, OmmaErr> {
 pub fn current_object_id() -> u32 {
     OBJECT_ID.load(Ordering::Relaxed)
 }
+
+pub fn next_effect_id() -> Result<u32, OmmaErr> {
+    let current_id = EFFECT_ID.load(Ordering::Relaxed);
+    let id = EFFECT_ID.fetch_add(1, Ordering::Relaxed);
+    if current_id > id {
+        return Err(OmmaErr::new(
+            "ommatidium effect_id tracker wrapped u32::MAX. Failed State.",
+        ));
+    }
+    Ok(id)
+}
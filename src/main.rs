@@ -1,11 +1,37 @@
 use ommatidium::border::*;
 use ommatidium::cell::*;
-use ommatidium::color::{BLACK, BLUE, DARK_GREY, GREEN, RED};
+use ommatidium::color::{BLUE, DARK_GREY, GREEN};
 use ommatidium::error::OmmaErr;
+use ommatidium::key::*;
+use ommatidium::menu::{Menu, MenuEvent};
+use ommatidium::object::ObjectStyle;
 use ommatidium::session::Session;
+use ommatidium::shadow::OmmaShadow;
+use ommatidium::theme::{OmmaRole, OmmaTheme};
+
+const DUSK: &str = "
+background = on black
+border = #81a2be
+title = bold #f0c674
+text = #c5c8c6
+accent = #b5bd68
+danger = #cc6666
+";
+
+const DAWN: &str = "
+background = on #fdf6e3
+border = #93a1a1
+title = bold #b58900
+text = #586e75
+accent = #859900
+danger = #dc322f
+";
 
 fn hello() -> Result<(), OmmaErr> {
     let mut session = Session::new()?;
+    let themes = [OmmaTheme::parse(DUSK)?, OmmaTheme::parse(DAWN)?];
+    let mut theme = 0;
+    session.set_theme(&themes[theme]);
 
     // TODO: Remove the need for this backdrop
     session
@@ -38,29 +64,30 @@ fn hello() -> Result<(), OmmaErr> {
         .border_raw(&HORIZ_CELL, &VERT_CELL, &CORNER_CELL)
         .submit(&mut session)?;
 
-    let positive = OmmaCell {
-        fg: Some(GREEN),
-        ..DEFAULT_CELL
-    };
-    let negative = OmmaCell {
-        fg: Some(RED),
-        ..DEFAULT_CELL
-    };
-    let blank_black = OmmaCell {
-        bg: Some(BLACK),
-        ..BLANK_CELL
-    };
+    let background = OmmaCell::themed(OmmaRole::Background);
     let id_dialog = session
         .new_window(25, 9)
         .offset(11, 3)
-        .fill(&blank_black)
+        .fill(&background)
         .border(&BOX_HOLLOW_BORDER)
         .pad_mono(2)
+        .shadow(&OmmaShadow::default())
         .submit(&mut session)?;
     let window = session.window(id_dialog)?;
-    window.string_raw(0, 0, &BLANK_CELL, "Hello Dungeon!".to_string())?;
-    window.string_raw(0, 1, &positive, "Yes!".to_string())?;
-    window.string_raw(7, 1, &negative, "No!".to_string())?;
+    window.markup(0, 0, &background, "[title]Hello Dungeon![/]")?;
+
+    // The choices sit in a child window under the title so the menu can fill its content area
+    let id_choices = session
+        .new_window(19, 2)
+        .offset(0, 1)
+        .parent(id_dialog)
+        .fill(&background)
+        .submit(&mut session)?;
+    let mut choices = Menu::new()
+        .item("Yes!", true)
+        .item("No!", false)
+        .cell(&OmmaCell::themed(OmmaRole::Accent));
+    choices.draw(session.window(id_choices)?)?;
 
     let id_zone = session
         .new_window(20, 20)
@@ -69,6 +96,13 @@ fn hello() -> Result<(), OmmaErr> {
         .border_mono(&WALL_CELL)
         .submit(&mut session)?;
 
+    // A pillar in the middle of the zone that blocks movement
+    let zone = session.window(id_zone)?;
+    for y in 6..9 {
+        zone.string_raw(8, y, &WALL_CELL, "##".to_string())?;
+    }
+    zone.solidify(&WALL_CELL);
+
     let player = OmmaCell {
         fg: Some(BLUE),
         ..PLAYER_CELL
@@ -82,6 +116,7 @@ fn hello() -> Result<(), OmmaErr> {
     let id_player = session
         .new_object()
         .name("Player".to_string())
+        .group("party")
         .offset(3, 2)
         .parent(id_zone)
         .cell(&player)
@@ -90,6 +125,7 @@ fn hello() -> Result<(), OmmaErr> {
     let id_goblin = session
         .new_object()
         .name("Goblin 1".to_string())
+        .group("monsters")
         .offset(1, 1)
         .parent(id_zone)
         .cell(&goblin)
@@ -98,15 +134,66 @@ fn hello() -> Result<(), OmmaErr> {
     loop {
         session.render()?;
         if let Some(key) = session.read_key()? {
+            // The dialog's menu gets the first look at keys while it is open
+            if !session.window(id_dialog)?.is_hidden() {
+                match choices.handle_key(key) {
+                    // At the ends of the list the menu's keys stay with it instead of moving
+                    // the player
+                    MenuEvent::Ignored
+                        if matches!(
+                            key,
+                            KEY_UP
+                                | KEY_DOWN
+                                | KEY_ARROW_UP
+                                | KEY_ARROW_DOWN
+                                | KEY_ENTER
+                                | KEY_SPACE
+                        ) =>
+                    {
+                        continue;
+                    }
+                    MenuEvent::Ignored => (),
+                    MenuEvent::Moved(_) => {
+                        choices.draw(session.window(id_choices)?)?;
+                        continue;
+                    }
+                    MenuEvent::Chosen(_) | MenuEvent::Cancelled => {
+                        session.window(id_dialog)?.set_hidden();
+                        continue;
+                    }
+                }
+            }
             match key {
                 'S' => break,
-                'p' => session.object(id_player)?.toggle_hidden(),
-                'h' => session.window(id_dialog)?.toggle_hidden(),
+                'p' => session.toggle_group("party"),
+                KEY_LEFT | KEY_DOWN | KEY_UP | KEY_RIGHT | KEY_ARROW_LEFT | KEY_ARROW_DOWN
+                | KEY_ARROW_UP | KEY_ARROW_RIGHT => {
+                    let (dx, dy) = match key {
+                        KEY_LEFT | KEY_ARROW_LEFT => (-1, 0),
+                        KEY_DOWN | KEY_ARROW_DOWN => (0, 1),
+                        KEY_UP | KEY_ARROW_UP => (0, -1),
+                        _ => (1, 0),
+                    };
+                    session.object_handle(id_player)?.try_move(dx, dy)?;
+                }
+                'd' => session.window(id_dialog)?.toggle_hidden(),
+                't' => {
+                    theme = (theme + 1) % themes.len();
+                    session.set_theme(&themes[theme]);
+                }
                 'q' => session.window(id_world)?.toggle_border_hidden(),
                 'w' => session.window(id_transparent)?.toggle_border_hidden(),
                 'e' => session.window(id_dialog)?.toggle_border_hidden(),
                 'z' => session.window(id_zone)?.toggle_hidden(),
-                'g' => session.object(id_goblin)?.toggle_hidden(),
+                'g' => session.toggle_group("monsters"),
+                'b' => {
+                    let goblin = session.object(id_goblin)?;
+                    if goblin.style().is_some() {
+                        goblin.clear_style();
+                    } else {
+                        goblin.set_style(&ObjectStyle::blink());
+                    }
+                }
                 '\x03' => break,
                 _ => continue,
             }
@@ -0,0 +1,306 @@
+use crate::cell::{ATTR_DIM, ATTR_REVERSE, DEFAULT_CELL, OmmaCell};
+use crate::error::OmmaErr;
+use crate::key::*;
+use crate::text::StyledText;
+use crate::theme::OmmaRole;
+use crate::window::Window;
+
+/// MenuEvent is the result of feeding a key into a menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEvent {
+    /// The key was not handled by the menu
+    Ignored,
+    /// The selection moved to the contained index
+    Moved(usize),
+    /// The item at the contained index was chosen
+    Chosen(usize),
+    /// The menu was dismissed without a choice
+    Cancelled,
+}
+
+#[derive(Clone)]
+pub struct MenuItem<T> {
+    label: StyledText,
+    value: T,
+    disabled: bool,
+}
+
+impl<T> MenuItem<T> {
+    pub fn label(&self) -> &str {
+        self.label.as_str()
+    }
+
+    pub fn styled_label(&self) -> &StyledText {
+        &self.label
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+}
+
+/// Menu is a vertical list of items with a selected index. The menu stores its items logically
+/// and only turns them into ommacells when drawn into a window.
+#[derive(Clone)]
+pub struct Menu<T> {
+    items: Vec<MenuItem<T>>,
+    selected: usize,
+    scroll: usize,
+    wrap: bool,
+    cell: OmmaCell,
+    highlight: OmmaCell,
+    disabled: OmmaCell,
+}
+
+impl<T> Default for Menu<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Menu<T> {
+    pub fn new() -> Self {
+        Menu {
+            items: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            wrap: false,
+            cell: OmmaCell {
+                role: Some(OmmaRole::Text),
+                ..DEFAULT_CELL
+            },
+            highlight: OmmaCell {
+                attrs: ATTR_REVERSE,
+                role: Some(OmmaRole::Selection),
+                ..DEFAULT_CELL
+            },
+            disabled: OmmaCell {
+                attrs: ATTR_DIM,
+                role: Some(OmmaRole::Disabled),
+                ..DEFAULT_CELL
+            },
+        }
+    }
+
+    /// item appends a selectable item to the menu
+    pub fn item(mut self, label: impl Into<StyledText>, value: T) -> Self {
+        self.push(label, value, false);
+        self
+    }
+
+    /// item_disabled appends an item that is drawn but can not be selected
+    pub fn item_disabled(mut self, label: impl Into<StyledText>, value: T) -> Self {
+        self.push(label, value, true);
+        self
+    }
+
+    /// wrap makes navigation past either end of the menu continue from the other end
+    pub fn wrap(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+
+    /// cell sets the style used for unselected items
+    pub fn cell(mut self, cell: &OmmaCell) -> Self {
+        self.cell = cell.clone();
+        self
+    }
+
+    /// highlight sets the style used for the selected item
+    pub fn highlight(mut self, cell: &OmmaCell) -> Self {
+        self.highlight = cell.clone();
+        self
+    }
+
+    /// disabled sets the style used for disabled items
+    pub fn disabled(mut self, cell: &OmmaCell) -> Self {
+        self.disabled = cell.clone();
+        self
+    }
+
+    /// push appends an item, moving the selection onto it if nothing selectable was selected
+    pub fn push(&mut self, label: impl Into<StyledText>, value: T, disabled: bool) {
+        self.items.push(MenuItem {
+            label: label.into(),
+            value,
+            disabled,
+        });
+        if self.items[self.selected].disabled && !disabled {
+            self.selected = self.items.len() - 1;
+        }
+    }
+
+    pub fn items(&self) -> &[MenuItem<T>] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// selected_item returns the currently selected item if it can be chosen
+    pub fn selected_item(&self) -> Option<&MenuItem<T>> {
+        self.items.get(self.selected).filter(|item| !item.disabled)
+    }
+
+    /// value returns the value of the item at index
+    pub fn value(&self, index: usize) -> Option<&T> {
+        self.items.get(index).map(|item| &item.value)
+    }
+
+    /// set_disabled enables or disables the item at index
+    pub fn set_disabled(&mut self, index: usize, disabled: bool) -> Result<(), OmmaErr> {
+        let len = self.items.len();
+        let Some(item) = self.items.get_mut(index) else {
+            return Err(OmmaErr::new(&format!(
+                "invalid menu item {}, menu has {} items",
+                index, len
+            )));
+        };
+        item.disabled = disabled;
+        if disabled && index == self.selected {
+            let _ = self.select_next() || self.select_prev();
+        }
+        Ok(())
+    }
+
+    /// select moves the selection to index if that item is enabled
+    pub fn select(&mut self, index: usize) -> Result<(), OmmaErr> {
+        match self.items.get(index) {
+            Some(item) if !item.disabled => {
+                self.selected = index;
+                Ok(())
+            }
+            _ => Err(OmmaErr::new(&format!(
+                "menu item {} is not selectable",
+                index
+            ))),
+        }
+    }
+
+    /// select_next moves the selection down to the next enabled item, returns true if it moved
+    pub fn select_next(&mut self) -> bool {
+        let len = self.items.len();
+        for step in 1..len {
+            let index = self.selected + step;
+            if index >= len && !self.wrap {
+                break;
+            }
+            if !self.items[index % len].disabled {
+                self.selected = index % len;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// select_prev moves the selection up to the previous enabled item, returns true if it moved
+    pub fn select_prev(&mut self) -> bool {
+        let len = self.items.len();
+        for step in 1..len {
+            if step > self.selected && !self.wrap {
+                break;
+            }
+            let index = (self.selected + len - step) % len;
+            if !self.items[index].disabled {
+                self.selected = index;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// handle_key applies a keypress to the menu and reports what happened
+    pub fn handle_key(&mut self, key: char) -> MenuEvent {
+        match key {
+            KEY_UP | KEY_ARROW_UP if self.select_prev() => MenuEvent::Moved(self.selected),
+            KEY_DOWN | KEY_ARROW_DOWN if self.select_next() => MenuEvent::Moved(self.selected),
+            KEY_UP | KEY_ARROW_UP | KEY_DOWN | KEY_ARROW_DOWN => MenuEvent::Ignored,
+            KEY_ENTER | KEY_SPACE => match self.selected_item() {
+                Some(_) => MenuEvent::Chosen(self.selected),
+                None => MenuEvent::Ignored,
+            },
+            KEY_ESCAPE => MenuEvent::Cancelled,
+            _ => MenuEvent::Ignored,
+        }
+    }
+
+    /// scroll_to_selected adjusts the scroll so the selection is within rows visible lines
+    fn scroll_to_selected(&mut self, rows: usize) {
+        if rows == 0 {
+            return;
+        }
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+        self.scroll = self.scroll.min(self.items.len().saturating_sub(rows));
+    }
+
+    /// draw writes the visible part of the menu into the content area of a window, returns the
+    /// number of cells written
+    pub fn draw(&mut self, window: &mut Window) -> Result<u32, OmmaErr> {
+        let rows = window.content_height();
+        let cols = window.content_width();
+        self.scroll_to_selected(rows);
+
+        let mut written = 0;
+        for y in 0..rows {
+            let index = self.scroll + y;
+            let (label, style) = match self.items.get(index) {
+                Some(item) if item.disabled => (Some(&item.label), &self.disabled),
+                Some(item) if index == self.selected => (Some(&item.label), &self.highlight),
+                Some(item) => (Some(&item.label), &self.cell),
+                None => (None, &self.cell),
+            };
+            let mut cells = label
+                .map(|label| label.clip_cells(style, cols))
+                .unwrap_or_default();
+            cells.resize(
+                cols,
+                OmmaCell {
+                    ch: ' ',
+                    ..style.clone()
+                },
+            );
+            for (x, cell) in cells.iter().enumerate() {
+                window.set_ommacell(x, y, cell)?;
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+}
+
+#[test]
+fn navigation() {
+    let mut menu = Menu::new()
+        .item_disabled("Locked", 0)
+        .item("Yes", 1)
+        .item_disabled("Maybe", 2)
+        .item("No", 3);
+    assert!(menu.selected() == 1);
+    assert!(menu.handle_key(KEY_DOWN) == MenuEvent::Moved(3));
+    assert!(menu.handle_key(KEY_DOWN) == MenuEvent::Ignored);
+    assert!(menu.handle_key(KEY_ARROW_UP) == MenuEvent::Moved(1));
+    assert!(menu.handle_key(KEY_UP) == MenuEvent::Ignored);
+    assert!(menu.handle_key(KEY_ENTER) == MenuEvent::Chosen(1));
+    assert!(menu.value(menu.selected()) == Some(&1));
+}
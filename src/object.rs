@@ -1,7 +1,77 @@
-use crate::cell::OmmaCell;
+use crate::cell::{ATTR_BLINK, EMPTY, OmmaCell};
+use crate::clock::OmmaClock;
+use crate::color::OmmaColor;
 use crate::error::OmmaErr;
 use crate::session::Session;
+use crate::shader::{OmmaShader, ShaderCtx};
+use crate::sprite::OmmaSprite;
 use crate::term::OmmaTerm;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+/// Layers for floor items, creatures and effects. Objects default to LAYER_CREATURES.
+pub const LAYER_ITEMS: i32 = -1;
+pub const LAYER_CREATURES: i32 = 0;
+pub const LAYER_EFFECTS: i32 = 1;
+
+/// ObjectStyle overrides how an object is drawn without touching its cells
+#[derive(Clone, Default)]
+pub struct ObjectStyle {
+    /// Replaces the fg of every cell
+    pub fg: Option<OmmaColor>,
+    /// Replaces the bg of every cell
+    pub bg: Option<OmmaColor>,
+    /// Mixes fg and bg toward the color by an amount in 0.0..=1.0
+    pub tint: Option<(OmmaColor, f64)>,
+    /// Attribute bits added to every cell
+    pub attrs: u16,
+}
+
+impl ObjectStyle {
+    /// tint creates a style mixing the object's colors toward color by amount
+    pub fn tint(color: &OmmaColor, amount: f64) -> Self {
+        ObjectStyle {
+            tint: Some((*color, amount)),
+            ..Default::default()
+        }
+    }
+
+    /// blink creates a style making the object blink
+    pub fn blink() -> Self {
+        ObjectStyle {
+            attrs: ATTR_BLINK,
+            ..Default::default()
+        }
+    }
+
+    /// apply returns cell as drawn with the style
+    pub fn apply(&self, cell: &OmmaCell) -> OmmaCell {
+        let mut cell = cell.clone();
+        if let Some(fg) = &self.fg {
+            cell.fg = Some(*fg);
+        }
+        if let Some(bg) = &self.bg {
+            cell.bg = Some(*bg);
+        }
+        if let Some((color, amount)) = &self.tint {
+            cell.fg = cell.fg.map(|fg| fg.lerp(color, *amount));
+            cell.bg = cell.bg.map(|bg| bg.lerp(color, *amount));
+        }
+        cell.attrs |= self.attrs;
+        cell
+    }
+}
+
+/// MoveBlocker reports what stopped an object from moving
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveBlocker {
+    /// The object would leave its window's content
+    Edge,
+    /// A solid cell of the window's collision layer at x, y
+    Wall(usize, usize),
+    /// Another object that is not passable
+    Object(u32),
+}
 
 #[allow(dead_code)]
 #[derive(Default, Clone)]
@@ -12,7 +82,14 @@ pub struct Object {
     offset_x: usize,
     offset_y: usize,
     hidden: bool,
-    cell: Option<OmmaCell>,
+    passable: bool,
+    layer: i32,
+    groups: Vec<String>,
+    group_hidden: bool,
+    style: Option<ObjectStyle>,
+    flash: Option<ObjectStyle>,
+    sprite: Option<OmmaSprite>,
+    shaders: Vec<Rc<dyn OmmaShader>>,
 }
 
 #[allow(dead_code)]
@@ -23,7 +100,11 @@ pub struct ObjectBuilder {
     offset_x: usize,
     offset_y: usize,
     hidden: bool,
-    cell: Option<OmmaCell>,
+    passable: bool,
+    layer: i32,
+    groups: Vec<String>,
+    sprite: Option<OmmaSprite>,
+    shaders: Vec<Rc<dyn OmmaShader>>,
 }
 
 impl ObjectBuilder {
@@ -34,7 +115,11 @@ impl ObjectBuilder {
             offset_x: 0,
             offset_y: 0,
             hidden: false,
-            cell: None,
+            passable: false,
+            layer: LAYER_CREATURES,
+            groups: Vec::new(),
+            sprite: None,
+            shaders: Vec::new(),
         }
     }
 
@@ -63,9 +148,52 @@ impl ObjectBuilder {
         self
     }
 
+    /// passable lets other objects move through this one, such as items lying on the floor
+    pub fn passable(mut self) -> Self {
+        self.passable = true;
+        self
+    }
+
+    /// layer sets the layer the object is drawn in, higher layers are drawn on top and objects
+    /// within a layer are drawn in the order they were added
+    pub fn layer(mut self, layer: i32) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    /// group adds the object to a named visibility group, see Session::toggle_group
+    pub fn group(mut self, group: &str) -> Self {
+        self.groups.push(group.to_string());
+        self
+    }
+
     /// cell sets an object to contain the given OmmaCell
     pub fn cell(mut self, cell: &OmmaCell) -> Self {
-        self.cell = Some(cell.clone());
+        self.sprite = Some(OmmaSprite::from_cell(cell));
+        self
+    }
+
+    /// text sets an object to contain a row of text drawn with the properties of cell
+    pub fn text(mut self, text: &str, cell: &OmmaCell) -> Self {
+        self.sprite = Some(OmmaSprite::from_str(text, cell));
+        self
+    }
+
+    /// cells sets an object to contain a styled string of cells
+    pub fn cells(mut self, cells: &[OmmaCell]) -> Self {
+        self.sprite = Some(OmmaSprite::from_cells(cells));
+        self
+    }
+
+    /// sprite sets an object to contain a multi-cell sprite, its top left cell sits at the offset
+    pub fn sprite(mut self, sprite: &OmmaSprite) -> Self {
+        self.sprite = Some(sprite.clone());
+        self
+    }
+
+    /// shader appends a shader applied to the object's cell at blit time
+    pub fn shader(mut self, shader: Rc<dyn OmmaShader>) -> Self {
+        self.shaders.push(shader);
         self
     }
 
@@ -84,7 +212,14 @@ impl ObjectBuilder {
             offset_x: self.offset_x,
             offset_y: self.offset_y,
             hidden: self.hidden,
-            cell: self.cell.clone(),
+            passable: self.passable,
+            layer: self.layer,
+            groups: self.groups.clone(),
+            group_hidden: false,
+            style: None,
+            flash: None,
+            sprite: self.sprite.clone(),
+            shaders: self.shaders.clone(),
         };
         let id = session.register_object(object)?;
         Ok(id)
@@ -120,6 +255,141 @@ impl Object {
         self.offset_y
     }
 
+    /// set_offset moves the object within its window without any checks, see ObjectHandle for
+    /// moves checked against the window and other objects
+    pub fn set_offset(&mut self, offset_x: usize, offset_y: usize) {
+        self.offset_x = offset_x;
+        self.offset_y = offset_y;
+    }
+
+    pub fn layer(&self) -> i32 {
+        self.layer
+    }
+
+    pub fn set_layer(&mut self, layer: i32) {
+        self.layer = layer;
+    }
+
+    pub fn groups(&self) -> &[String] {
+        &self.groups
+    }
+
+    pub fn in_group(&self, group: &str) -> bool {
+        self.groups.iter().any(|name| name == group)
+    }
+
+    /// add_group and remove_group change the object's groups, Session::add_object_group and
+    /// remove_object_group also update its visibility
+    pub(crate) fn add_group(&mut self, group: &str) {
+        if !self.in_group(group) {
+            self.groups.push(group.to_string());
+        }
+    }
+
+    pub(crate) fn remove_group(&mut self, group: &str) {
+        self.groups.retain(|name| name != group);
+    }
+
+    /// set_group_hidden records whether one of the object's groups is hidden
+    pub(crate) fn set_group_hidden(&mut self, group_hidden: bool) {
+        self.group_hidden = group_hidden;
+    }
+
+    /// is_visible returns false if the object or one of its groups is hidden
+    pub fn is_visible(&self) -> bool {
+        !self.hidden && !self.group_hidden
+    }
+
+    pub fn style(&self) -> Option<&ObjectStyle> {
+        self.style.as_ref()
+    }
+
+    /// set_style overrides how the object is drawn until clear_style is called
+    pub fn set_style(&mut self, style: &ObjectStyle) {
+        self.style = Some(style.clone());
+    }
+
+    pub fn clear_style(&mut self) {
+        self.style = None;
+    }
+
+    /// flash returns the style of the flash running on the object, drawn in place of its style
+    pub fn flash(&self) -> Option<&ObjectStyle> {
+        self.flash.as_ref()
+    }
+
+    /// set_flash replaces the flash layer, leaving the object's own style untouched
+    pub(crate) fn set_flash(&mut self, flash: Option<&ObjectStyle>) {
+        self.flash = flash.cloned();
+    }
+
+    pub fn is_passable(&self) -> bool {
+        self.passable
+    }
+
+    pub fn set_passable(&mut self, passable: bool) {
+        self.passable = passable;
+    }
+
+    /// width returns the number of columns the object covers
+    pub fn width(&self) -> usize {
+        self.sprite.as_ref().map_or(0, |sprite| sprite.width())
+    }
+
+    /// height returns the number of rows the object covers
+    pub fn height(&self) -> usize {
+        self.sprite.as_ref().map_or(0, |sprite| sprite.height())
+    }
+
+    /// cell returns the top left cell of the object
+    pub fn cell(&self) -> Option<&OmmaCell> {
+        self.sprite.as_ref().and_then(|sprite| sprite.get(0, 0))
+    }
+
+    /// set_cell replaces the object's contents with a single cell
+    pub fn set_cell(&mut self, cell: &OmmaCell) {
+        self.sprite = Some(OmmaSprite::from_cell(cell));
+    }
+
+    pub fn sprite(&self) -> Option<&OmmaSprite> {
+        self.sprite.as_ref()
+    }
+
+    pub fn sprite_mut(&mut self) -> Option<&mut OmmaSprite> {
+        self.sprite.as_mut()
+    }
+
+    pub fn set_sprite(&mut self, sprite: &OmmaSprite) {
+        self.sprite = Some(sprite.clone());
+    }
+
+    /// contains returns true if x, y in the parent's content falls within the object's extent
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.offset_x
+            && y >= self.offset_y
+            && x < self.offset_x + self.width()
+            && y < self.offset_y + self.height()
+    }
+
+    /// hit returns true if x, y in the parent's content lands on an opaque cell of the object
+    pub fn hit(&self, x: usize, y: usize) -> bool {
+        self.is_visible()
+            && self.contains(x, y)
+            && self
+                .sprite
+                .as_ref()
+                .is_some_and(|sprite| sprite.is_opaque(x - self.offset_x, y - self.offset_y))
+    }
+
+    /// release hides the object and drops its cell once it has been detached from its window
+    pub(crate) fn release(&mut self) {
+        self.hidden = true;
+        self.sprite = None;
+        self.style = None;
+        self.flash = None;
+        self.shaders.clear();
+    }
+
     pub fn is_hidden(&self) -> bool {
         self.hidden
     }
@@ -136,20 +406,129 @@ impl Object {
         self.hidden = false
     }
 
+    /// add_shader appends a shader applied to the object's cell at blit time
+    pub fn add_shader(&mut self, shader: Rc<dyn OmmaShader>) {
+        self.shaders.push(shader);
+    }
+
+    pub fn clear_shaders(&mut self) {
+        self.shaders.clear();
+    }
+
+    /// blit draws the object relative to its parent's content origin, clipped to the part of the
+    /// parent's content inside view
     pub fn blit(
         &mut self,
         term: &mut OmmaTerm,
-        parent_x: usize,
-        parent_y: usize,
+        view: &ObjectView,
+        clock: &OmmaClock,
     ) -> Result<u32, OmmaErr> {
-        if self.hidden {
+        if !self.is_visible() {
             return Ok(0);
         }
+        let Some(sprite) = &self.sprite else {
+            return Ok(0);
+        };
         let mut written = 0;
-        if let Some(cell) = &self.cell {
-            written +=
-                term.put_cell_at(self.offset_x + parent_x, self.offset_y + parent_y, cell)?;
+        for x in 0..sprite.width() {
+            for y in 0..sprite.height() {
+                let (content_x, content_y) = (self.offset_x + x, self.offset_y + y);
+                if content_x < view.scroll_x
+                    || content_y < view.scroll_y
+                    || content_x >= view.scroll_x + view.width
+                    || content_y >= view.scroll_y + view.height
+                {
+                    continue;
+                }
+                let Some(cell) = sprite.get(x, y) else {
+                    continue;
+                };
+                let screen_x = content_x - view.scroll_x + view.origin_x;
+                let screen_y = content_y - view.scroll_y + view.origin_y;
+                if cell.ch == EMPTY {
+                    continue;
+                }
+                let ctx = ShaderCtx {
+                    screen_x,
+                    screen_y,
+                    local_x: x,
+                    local_y: y,
+                    frame: clock.frame(),
+                    elapsed: clock.elapsed(),
+                    window_id: self.parent_id,
+                };
+                let mut cell = cell.clone();
+                for shader in &self.shaders {
+                    cell = shader.shade(&cell, &ctx);
+                }
+                if let Some(style) = self.flash.as_ref().or(self.style.as_ref()) {
+                    cell = style.apply(&cell);
+                }
+                if content_x + 1 == view.scroll_x + view.width && cell.is_wide() {
+                    // The right half of a wide character would fall outside the view
+                    cell.ch = ' ';
+                    cell.tail = None;
+                }
+                written += term.put_cell_at(screen_x, screen_y, &cell)?;
+            }
         }
         Ok(written)
     }
 }
+
+/// ObjectHandle is an object borrowed together with its session from Session::object_handle. It
+/// derefs to the Object and adds moves checked against the window's content bounds, its collision
+/// layer and the other objects in it, which the Object alone can not see.
+pub struct ObjectHandle<'a> {
+    session: &'a mut Session,
+    object_id: u32,
+}
+
+impl<'a> ObjectHandle<'a> {
+    /// new borrows object_id from session, errors if there is no such object
+    pub(crate) fn new(session: &'a mut Session, object_id: u32) -> Result<Self, OmmaErr> {
+        session.object(object_id)?;
+        Ok(ObjectHandle { session, object_id })
+    }
+
+    /// move_to moves the object to x, y of its window's content, errors if it is blocked
+    pub fn move_to(&mut self, x: usize, y: usize) -> Result<(), OmmaErr> {
+        self.session.move_object_to(self.object_id, x, y)
+    }
+
+    /// move_by moves the object by dx, dy within its window's content, errors if it is blocked
+    pub fn move_by(&mut self, dx: isize, dy: isize) -> Result<(), OmmaErr> {
+        self.session.move_object_by(self.object_id, dx, dy)
+    }
+
+    /// try_move moves the object by dx, dy within its window's content unless something blocks
+    /// it, returns the blocker when the object stays put
+    pub fn try_move(&mut self, dx: isize, dy: isize) -> Result<Option<MoveBlocker>, OmmaErr> {
+        self.session.try_move_object(self.object_id, dx, dy)
+    }
+}
+
+impl Deref for ObjectHandle<'_> {
+    type Target = Object;
+
+    fn deref(&self) -> &Object {
+        &self.session.objects()[self.object_id as usize]
+    }
+}
+
+impl DerefMut for ObjectHandle<'_> {
+    fn deref_mut(&mut self) -> &mut Object {
+        &mut self.session.objects_mut()[self.object_id as usize]
+    }
+}
+
+/// ObjectView is the visible part of a window's content that objects are clipped to
+pub struct ObjectView {
+    /// Screen position of the visible part, which shows the content at scroll_x, scroll_y
+    pub origin_x: usize,
+    pub origin_y: usize,
+    pub scroll_x: usize,
+    pub scroll_y: usize,
+    pub width: usize,
+    pub height: usize,
+}
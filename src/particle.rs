@@ -0,0 +1,227 @@
+use crate::cell::{DEFAULT_CELL, EMPTY_CELL, OmmaCell};
+use crate::color::OmmaColor;
+use crate::effect::EffectTarget;
+use crate::error::OmmaErr;
+use crate::session::Session;
+use crate::window::{Window, WindowBuilder};
+use std::time::Duration;
+
+#[derive(Clone)]
+struct Particle {
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+    age: Duration,
+    lifetime: Duration,
+    cell: OmmaCell,
+}
+
+/// ParticleEmitter spawns glyph particles that fly, fall and fade until their lifetime runs out.
+/// Positions are in cells of the window the emitter draws into, velocities in cells per second.
+#[derive(Clone)]
+pub struct ParticleEmitter {
+    x: f64,
+    y: f64,
+    particles: Vec<Particle>,
+    glyphs: Vec<char>,
+    color: Option<OmmaColor>,
+    fade_to: Option<OmmaColor>,
+    gravity: f64,
+    speed: (f64, f64),
+    angle: (f64, f64),
+    lifetime: Duration,
+    rng: u64,
+}
+
+impl ParticleEmitter {
+    /// new creates an emitter spawning particles at x, y
+    pub fn new(x: f64, y: f64) -> Self {
+        ParticleEmitter {
+            x,
+            y,
+            particles: Vec::new(),
+            glyphs: vec!['*'],
+            color: None,
+            fade_to: None,
+            gravity: 0.0,
+            speed: (2.0, 6.0),
+            angle: (0.0, std::f64::consts::TAU),
+            lifetime: Duration::from_millis(500),
+            rng: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    /// glyphs sets the characters particles are picked from
+    pub fn glyphs(mut self, glyphs: &[char]) -> Self {
+        if !glyphs.is_empty() {
+            self.glyphs = glyphs.to_vec();
+        }
+        self
+    }
+
+    /// color sets the foreground particles spawn with
+    pub fn color(mut self, color: &OmmaColor) -> Self {
+        self.color = Some(*color);
+        self
+    }
+
+    /// fade_to fades particles from their color to this one over their lifetime
+    pub fn fade_to(mut self, color: &OmmaColor) -> Self {
+        self.fade_to = Some(*color);
+        self
+    }
+
+    /// gravity accelerates particles downward in cells per second squared
+    pub fn gravity(mut self, gravity: f64) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    /// speed sets the range of spawn speeds in cells per second
+    pub fn speed(mut self, min: f64, max: f64) -> Self {
+        self.speed = (min, max);
+        self
+    }
+
+    /// spread sets the range of spawn directions in radians, 0 points right and y grows downward
+    pub fn spread(mut self, min: f64, max: f64) -> Self {
+        self.angle = (min, max);
+        self
+    }
+
+    /// lifetime sets how long each particle lives
+    pub fn lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// seed sets the random seed so the same emitter always produces the same particles
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = seed.max(1);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// random returns the next value in 0.0..1.0 from a xorshift generator
+    fn random(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn spawn_cell(&self, ch: char) -> OmmaCell {
+        OmmaCell {
+            ch,
+            fg: self.color,
+            ..DEFAULT_CELL
+        }
+    }
+
+    /// burst spawns count particles with random glyphs, speeds and directions
+    pub fn burst(&mut self, count: usize) {
+        for _ in 0..count {
+            let index = (self.random() * self.glyphs.len() as f64) as usize;
+            let glyph = self.glyphs[index];
+            let speed = self.speed.0 + (self.speed.1 - self.speed.0) * self.random();
+            let angle = self.angle.0 + (self.angle.1 - self.angle.0) * self.random();
+            self.particles.push(Particle {
+                x: self.x,
+                y: self.y,
+                vx: speed * angle.cos(),
+                vy: speed * angle.sin(),
+                age: Duration::ZERO,
+                lifetime: self.lifetime,
+                cell: self.spawn_cell(glyph),
+            });
+        }
+    }
+
+    /// emit_text spawns one particle per character of text moving together, such as a floating
+    /// damage number
+    pub fn emit_text(&mut self, text: &str, vx: f64, vy: f64) {
+        for (index, ch) in text.chars().enumerate() {
+            self.particles.push(Particle {
+                x: self.x + index as f64,
+                y: self.y,
+                vx,
+                vy,
+                age: Duration::ZERO,
+                lifetime: self.lifetime,
+                cell: self.spawn_cell(ch),
+            });
+        }
+    }
+
+    /// step advances every particle by delta and drops the ones that have expired
+    pub fn step(&mut self, delta: Duration) {
+        let dt = delta.as_secs_f64();
+        for particle in &mut self.particles {
+            particle.age += delta;
+            particle.vy += self.gravity * dt;
+            particle.x += particle.vx * dt;
+            particle.y += particle.vy * dt;
+        }
+        self.particles
+            .retain(|particle| particle.age < particle.lifetime);
+    }
+
+    /// draw clears a window to transparent and writes every particle inside it
+    pub fn draw(&self, window: &mut Window) -> Result<u32, OmmaErr> {
+        window.fill(&EMPTY_CELL)?;
+        let mut written = 0;
+        for particle in &self.particles {
+            let (x, y) = (particle.x.round(), particle.y.round());
+            if x < 0.0 || y < 0.0 || x as usize >= window.width() || y as usize >= window.height() {
+                continue;
+            }
+            let mut cell = particle.cell.clone();
+            if let (Some(from), Some(to)) = (&cell.fg, &self.fade_to) {
+                let t =
+                    particle.age.as_secs_f64() / particle.lifetime.as_secs_f64().max(f64::EPSILON);
+                cell.fg = Some(from.lerp(to, t));
+            }
+            window.set_ommacell(x as usize, y as usize, &cell)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// submit creates a window from builder for the emitter to draw into and steps the emitter on
+    /// every session tick. The window is retired once the last particle expires.
+    pub fn submit(mut self, session: &mut Session, builder: WindowBuilder) -> Result<u32, OmmaErr> {
+        let id = builder.submit(session)?;
+        self.draw(session.window(id)?)?;
+        session
+            .new_effect(EffectTarget::Window(id))
+            .update(move |session, tick| {
+                self.step(tick.delta);
+                self.draw(session.window(id)?)?;
+                Ok(!self.is_empty())
+            })
+            .submit(session)?;
+        Ok(id)
+    }
+}
+
+#[test]
+fn step() {
+    let mut emitter = ParticleEmitter::new(0.0, 0.0)
+        .gravity(10.0)
+        .lifetime(Duration::from_secs(1));
+    emitter.emit_text("12", 1.0, 0.0);
+    emitter.step(Duration::from_millis(500));
+    assert!(emitter.len() == 2);
+    assert!(emitter.particles[0].x == 0.5);
+    assert!(emitter.particles[0].y == 2.5);
+    emitter.step(Duration::from_millis(500));
+    assert!(emitter.is_empty());
+}
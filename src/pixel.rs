@@ -0,0 +1,323 @@
+use crate::canvas::{OmmaRegion, line_points};
+use crate::cell::OmmaCell;
+use crate::color::OmmaColor;
+use crate::error::OmmaErr;
+use crate::window::Window;
+
+/// PixelMode picks the glyphs an OmmaPixels canvas packs its pixels into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelMode {
+    /// 2x4 dots per cell, every dot of a cell shares one color
+    #[default]
+    Braille,
+    /// 1x2 pixels per cell from ▀ and ▄, each pixel keeps its own color
+    HalfBlock,
+    /// 2x2 pixels per cell from the quadrant blocks, at most two colors per cell
+    Quadrant,
+}
+
+impl PixelMode {
+    /// pixels returns the number of pixels across and down each cell
+    pub fn pixels(&self) -> (usize, usize) {
+        match self {
+            PixelMode::Braille => (2, 4),
+            PixelMode::HalfBlock => (1, 2),
+            PixelMode::Quadrant => (2, 2),
+        }
+    }
+}
+
+/// Braille dot bits by pixel position within the cell, indexed [x][y]
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// Quadrant blocks indexed by their lit quadrants, top left 1, top right 2, bottom left 4 and
+/// bottom right 8
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+/// OmmaPixels is a grid of pixels finer than the cell grid, for charts, sparklines and minimaps.
+/// Pixels are either lit in a color or unlit, and are packed into braille or block glyphs when
+/// drawn into a window, or each time a window holding the canvas is drawn, see Window::set_pixels.
+#[derive(Clone, PartialEq)]
+pub struct OmmaPixels {
+    mode: PixelMode,
+    cols: usize,
+    rows: usize,
+    pixels: Vec<Option<OmmaColor>>,
+}
+
+impl OmmaPixels {
+    /// new creates an unlit canvas covering cols by rows cells
+    pub fn new(mode: PixelMode, cols: usize, rows: usize) -> Self {
+        let (across, down) = mode.pixels();
+        Self {
+            mode,
+            cols,
+            rows,
+            pixels: vec![None; cols * across * rows * down],
+        }
+    }
+
+    /// Getters
+    pub fn mode(&self) -> PixelMode {
+        self.mode
+    }
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// width returns the number of pixels across the canvas
+    pub fn width(&self) -> usize {
+        self.cols * self.mode.pixels().0
+    }
+
+    /// height returns the number of pixels down the canvas
+    pub fn height(&self) -> usize {
+        self.rows * self.mode.pixels().1
+    }
+
+    fn index(&self, x: isize, y: isize) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width() || y as usize >= self.height() {
+            return None;
+        }
+        Some(y as usize * self.width() + x as usize)
+    }
+
+    /// get returns the color of the pixel at x, y or None if it is unlit or outside the canvas
+    pub fn get(&self, x: isize, y: isize) -> Option<OmmaColor> {
+        self.index(x, y).and_then(|index| self.pixels[index])
+    }
+
+    /// set lights the pixel at x, y in color or turns it off with None, returns the number of
+    /// pixels set. The drawing methods below clip to the canvas the same way.
+    pub fn set(&mut self, x: isize, y: isize, color: Option<OmmaColor>) -> u32 {
+        match self.index(x, y) {
+            Some(index) => {
+                self.pixels[index] = color;
+                1
+            }
+            None => 0,
+        }
+    }
+
+    /// plot lights the pixel at x, y in color
+    pub fn plot(&mut self, x: isize, y: isize, color: &OmmaColor) -> u32 {
+        self.set(x, y, Some(*color))
+    }
+
+    /// line lights the pixels from x0, y0 to x1, y1 inclusive
+    pub fn line(&mut self, x0: isize, y0: isize, x1: isize, y1: isize, color: &OmmaColor) -> u32 {
+        line_points(x0, y0, x1, y1, self.width(), self.height())
+            .into_iter()
+            .map(|(x, y)| self.plot(x, y, color))
+            .sum()
+    }
+
+    /// rect_filled lights a width by height rectangle of pixels with its top left corner at x, y
+    pub fn rect_filled(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        color: &OmmaColor,
+    ) -> u32 {
+        let mut written = 0;
+        for x in x..x + width as isize {
+            for y in y..y + height as isize {
+                written += self.plot(x, y, color);
+            }
+        }
+        written
+    }
+
+    /// fill lights every pixel in color or turns them all off with None
+    pub fn fill(&mut self, color: Option<OmmaColor>) {
+        self.pixels.fill(color);
+    }
+
+    pub fn clear(&mut self) {
+        self.fill(None);
+    }
+
+    /// is_lit returns true if any pixel of the cell at col, row is lit
+    pub fn is_lit(&self, col: usize, row: usize) -> bool {
+        let (across, down) = self.mode.pixels();
+        (0..across).any(|x| {
+            (0..down).any(|y| {
+                self.get((col * across + x) as isize, (row * down + y) as isize)
+                    .is_some()
+            })
+        })
+    }
+
+    /// cell packs the pixels of the cell at col, row into a glyph drawn over base. Unlit pixels
+    /// show base's bg. Base's role is dropped from cells with lit pixels so a theme does not
+    /// recolor them.
+    pub fn cell(&self, col: usize, row: usize, base: &OmmaCell) -> OmmaCell {
+        let (across, down) = self.mode.pixels();
+        let pixel =
+            |x: usize, y: usize| self.get((col * across + x) as isize, (row * down + y) as isize);
+        match self.mode {
+            PixelMode::Braille => {
+                let mut dots = 0;
+                let mut lit = Vec::new();
+                for (x, column) in BRAILLE_DOTS.iter().enumerate() {
+                    for (y, dot) in column.iter().enumerate() {
+                        if let Some(color) = pixel(x, y) {
+                            dots |= dot;
+                            lit.push(Some(color));
+                        }
+                    }
+                }
+                if dots == 0 {
+                    return OmmaCell {
+                        ch: ' ',
+                        tail: None,
+                        ..base.clone()
+                    };
+                }
+                OmmaCell {
+                    ch: char::from_u32(0x2800 + dots).unwrap_or(' '),
+                    tail: None,
+                    fg: Self::ranked(&lit)[0],
+                    role: None,
+                    ..base.clone()
+                }
+            }
+            PixelMode::HalfBlock | PixelMode::Quadrant => {
+                let pixels: Vec<Option<OmmaColor>> = (0..down)
+                    .flat_map(|y| (0..across).map(move |x| pixel(x, y)))
+                    .collect();
+                let ranked = Self::ranked(&pixels);
+                let lit: Vec<OmmaColor> = ranked.iter().flatten().copied().collect();
+                let Some(&fg) = lit.first() else {
+                    return OmmaCell {
+                        ch: ' ',
+                        tail: None,
+                        ..base.clone()
+                    };
+                };
+                // Unlit pixels show base's bg, otherwise the second most common color is the bg
+                let bg = if ranked.contains(&None) {
+                    None
+                } else {
+                    lit.get(1).copied().or(Some(fg))
+                };
+                // Cells with more than two colors draw each pixel in whichever is nearer
+                let mut mask = 0;
+                for (bit, color) in pixels.iter().enumerate() {
+                    if let Some(color) = color
+                        && (*color == fg || (Some(*color) != bg && Self::nearer(color, &fg, bg)))
+                    {
+                        mask |= 1 << bit;
+                    }
+                }
+                let ch = match self.mode {
+                    PixelMode::HalfBlock => [' ', '▀', '▄', '█'][mask],
+                    _ => QUADRANTS[mask],
+                };
+                OmmaCell {
+                    ch,
+                    tail: None,
+                    fg: Some(fg),
+                    bg: bg.or(base.bg),
+                    role: None,
+                    ..base.clone()
+                }
+            }
+        }
+    }
+
+    /// ranked returns the distinct colors most common first, ties going to the first seen
+    fn ranked(colors: &[Option<OmmaColor>]) -> Vec<Option<OmmaColor>> {
+        let mut counts: Vec<(Option<OmmaColor>, usize)> = Vec::new();
+        for color in colors {
+            match counts.iter_mut().find(|(seen, _)| seen == color) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((*color, 1)),
+            }
+        }
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        counts.into_iter().map(|(color, _)| color).collect()
+    }
+
+    /// nearer returns true if color is closer to fg than to bg, unlit bgs are farthest
+    fn nearer(color: &OmmaColor, fg: &OmmaColor, bg: Option<OmmaColor>) -> bool {
+        let distance = |a: &OmmaColor, b: &OmmaColor| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(a.r, b.r) + d(a.g, b.g) + d(a.b, b.b)
+        };
+        bg.is_none_or(|bg| distance(color, fg) < distance(color, &bg))
+    }
+
+    /// region packs the whole canvas into cells drawn over base, see cell
+    pub fn region(&self, base: &OmmaCell) -> OmmaRegion {
+        let mut region = OmmaRegion::new(self.cols, self.rows, base);
+        for col in 0..self.cols {
+            for row in 0..self.rows {
+                let _ = region.set(col, row, &self.cell(col, row, base));
+            }
+        }
+        region
+    }
+
+    /// draw packs the canvas into cells drawn over base and writes them into window with their
+    /// top left corner at x, y, clipped to the window's content buffer
+    pub fn draw(
+        &self,
+        window: &mut Window,
+        x: usize,
+        y: usize,
+        base: &OmmaCell,
+    ) -> Result<u32, OmmaErr> {
+        if x >= window.width() || y >= window.height() {
+            return Err(OmmaErr::new(&format!(
+                "window_id {} invalid pixel canvas draw at {}:{} (max {}:{})",
+                window.id(),
+                x,
+                y,
+                window.width().saturating_sub(1),
+                window.height().saturating_sub(1)
+            )));
+        }
+        Ok(window.paste_region(x as isize, y as isize, &self.region(base), None))
+    }
+}
+
+#[test]
+fn pack() {
+    use crate::cell::DEFAULT_CELL;
+    use crate::color::{BLACK, BLUE, GREEN, RED};
+
+    // Braille dots take the most common lit color
+    let mut braille = OmmaPixels::new(PixelMode::Braille, 2, 1);
+    assert!(braille.width() == 4 && braille.height() == 4);
+    braille.line(0, 0, 1, 3, &RED);
+    braille.plot(0, 3, &GREEN);
+    let cell = braille.cell(0, 0, &DEFAULT_CELL);
+    assert!(cell.ch == '⣣' && cell.fg == Some(RED));
+    assert!(braille.cell(1, 0, &DEFAULT_CELL).ch == ' ');
+
+    // Half blocks keep both colors
+    let mut half = OmmaPixels::new(PixelMode::HalfBlock, 1, 1);
+    half.plot(0, 1, &BLUE);
+    let cell = half.cell(0, 0, &DEFAULT_CELL);
+    assert!(cell.ch == '▄' && cell.fg == Some(BLUE) && cell.bg.is_none());
+    half.plot(0, 0, &RED);
+    let cell = half.cell(0, 0, &DEFAULT_CELL);
+    assert!(cell.ch == '▀' && cell.fg == Some(RED) && cell.bg == Some(BLUE));
+
+    // Quadrants fall back to the nearer of the two most common colors
+    let mut quadrant = OmmaPixels::new(PixelMode::Quadrant, 1, 1);
+    quadrant.fill(Some(BLACK));
+    quadrant.plot(1, 0, &RED);
+    quadrant.plot(1, 1, &OmmaColor::new(200, 0, 0));
+    quadrant.plot(0, 1, &RED);
+    let cell = quadrant.cell(0, 0, &DEFAULT_CELL);
+    assert!(cell.ch == '▟' && cell.fg == Some(RED) && cell.bg == Some(BLACK));
+}
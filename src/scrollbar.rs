@@ -0,0 +1,80 @@
+use crate::cell::{DEFAULT_CELL, EMPTY_CELL, OmmaCell};
+use crate::theme::OmmaRole;
+
+#[derive(Clone)]
+#[allow(dead_code)]
+pub struct OmmaScrollbar {
+    track: OmmaCell,
+    thumb: OmmaCell,
+}
+
+impl Default for OmmaScrollbar {
+    fn default() -> Self {
+        Self::new(
+            &EMPTY_CELL,
+            &OmmaCell {
+                ch: '█',
+                role: Some(OmmaRole::Accent),
+                ..DEFAULT_CELL
+            },
+        )
+    }
+}
+
+impl OmmaScrollbar {
+    /// new creates a scrollbar style, an EMPTY track lets the border or pad beneath show through
+    pub fn new(track: &OmmaCell, thumb: &OmmaCell) -> Self {
+        Self {
+            track: track.clone(),
+            thumb: thumb.clone(),
+        }
+    }
+
+    /// Getters
+    pub fn track(&self) -> &OmmaCell {
+        &self.track
+    }
+    pub fn thumb(&self) -> &OmmaCell {
+        &self.thumb
+    }
+
+    /// thumb_span returns the start and length of the thumb on a track showing visible of total
+    /// cells scrolled by scroll, or None when everything fits
+    pub fn thumb_span(
+        track: usize,
+        visible: usize,
+        total: usize,
+        scroll: usize,
+    ) -> Option<(usize, usize)> {
+        if track == 0 || total <= visible {
+            return None;
+        }
+        let len = ((track * visible + total / 2) / total).clamp(1, track);
+        let max_scroll = total - visible;
+        let start = ((track - len) * scroll.min(max_scroll) + max_scroll / 2) / max_scroll;
+        Some((start, len))
+    }
+
+    /// scroll_at returns the scroll that centers the thumb on position pos of the track
+    pub fn scroll_at(track: usize, visible: usize, total: usize, pos: usize) -> usize {
+        let Some((_, len)) = Self::thumb_span(track, visible, total, 0) else {
+            return 0;
+        };
+        let travel = track - len;
+        if travel == 0 {
+            return 0;
+        }
+        let start = pos.saturating_sub(len / 2).min(travel);
+        let max_scroll = total - visible;
+        (start * max_scroll + travel / 2) / travel
+    }
+}
+
+#[test]
+fn thumb_span() {
+    // Half of the content visible gives a thumb half the track
+    assert!(OmmaScrollbar::thumb_span(10, 5, 10, 0) == Some((0, 5)));
+    assert!(OmmaScrollbar::thumb_span(10, 5, 10, 5) == Some((5, 5)));
+    assert!(OmmaScrollbar::thumb_span(10, 10, 10, 0).is_none());
+    assert!(OmmaScrollbar::scroll_at(10, 5, 10, 9) == 5);
+}
@@ -1,7 +1,16 @@
+use crate::cell::OmmaCell;
+use crate::clock::OmmaClock;
+use crate::effect::*;
 use crate::error::OmmaErr;
+use crate::key::OmmaInput;
 use crate::object::*;
 use crate::term::OmmaTerm;
+use crate::theme::OmmaTheme;
 use crate::window::*;
+use std::time::Duration;
+
+/// Headless sessions tick at a fixed 60 frames per second so frames are reproducible
+const HEADLESS_FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
 
 #[allow(dead_code)]
 pub struct Session {
@@ -9,6 +18,12 @@ pub struct Session {
     windows: Vec<Window>,
     objects: Vec<Object>,
     children: Vec<u32>,
+    clock: OmmaClock,
+    effects: Vec<Effect>,
+    cancelled: Vec<u32>,
+    hidden_groups: Vec<String>,
+    /// Running flashes in the order they started, as effect id, object id and style
+    flashes: Vec<(u32, u32, ObjectStyle)>,
 }
 
 #[allow(dead_code)]
@@ -16,23 +31,23 @@ impl Session {
     /// new creates a new session, it may only be invoked once per executable lifetime
     pub fn new() -> Result<Self, OmmaErr> {
         let term = OmmaTerm::new()?;
-        Self::new_inner(term)
+        Self::new_inner(term, OmmaClock::real())
     }
 
     /// new_headless creates a session with a fake max_row by max_col term
     pub fn new_headless(max_row: u16, max_col: u16) -> Result<Self, OmmaErr> {
         let term = OmmaTerm::new_mock(max_row, max_col)?;
-        Self::new_inner(term)
+        Self::new_inner(term, OmmaClock::fixed(HEADLESS_FRAME))
     }
 
     /// default_headless creates a session with a fake 50 by 50 term
     pub fn default_headless() -> Result<Self, OmmaErr> {
         let term = OmmaTerm::new_mock(50, 50)?;
-        Self::new_inner(term)
+        Self::new_inner(term, OmmaClock::fixed(HEADLESS_FRAME))
     }
 
     /// new_inner implements session init shared between headed/headless
-    fn new_inner(term: OmmaTerm) -> Result<Self, OmmaErr> {
+    fn new_inner(term: OmmaTerm, clock: OmmaClock) -> Result<Self, OmmaErr> {
         if crate::current_window_id() != 0 {
             return Err(OmmaErr::new(
                 "Session::new() may only be invoked once per executable lifetime",
@@ -44,6 +59,11 @@ impl Session {
             windows: Vec::new(),
             objects: Vec::new(),
             children: Vec::new(),
+            clock,
+            effects: Vec::new(),
+            cancelled: Vec::new(),
+            hidden_groups: Vec::new(),
+            flashes: Vec::new(),
         };
 
         // Reserve window id 0 with a valid window
@@ -100,6 +120,118 @@ impl Session {
         }
     }
 
+    /// retire_window detaches a window and its descendants from the tree and frees their contents.
+    /// Window ids are never reused, the retired id stays valid but is no longer drawn. Retiring a
+    /// window that is already detached does nothing.
+    pub fn retire_window(&mut self, window_id: u32) -> Result<(), OmmaErr> {
+        if window_id == 0 {
+            return Err(OmmaErr::new("the System Window can not be retired"));
+        }
+        let parent = self.window(window_id)?.parent_id();
+        if !self.window(parent)?.children().contains(&window_id) {
+            return Ok(());
+        }
+        self.window(parent)?.remove_child(window_id)?;
+        // A retired page leaves its tab container, which shows whichever page is active after
+        let active = self.window(parent)?.tabs_mut().and_then(|tabs| {
+            tabs.remove_page(window_id);
+            tabs.active_page()
+        });
+        if let Some(active) = active {
+            self.window(active)?.clear_hidden();
+        }
+        let mut retiring = vec![window_id];
+        while let Some(id) = retiring.pop() {
+            let window = self.window(id)?;
+            retiring.extend(window.children());
+            let objects = window.objects().to_vec();
+            window.release();
+            for object_id in objects {
+                self.object(object_id)?.release();
+            }
+        }
+        Ok(())
+    }
+
+    /// blit_window draws the whole content buffer of window source into window target with its
+    /// top left corner at x, y, skipping cells equal to key, returns the number of cells written
+    pub fn blit_window(
+        &mut self,
+        source: u32,
+        target: u32,
+        x: isize,
+        y: isize,
+        key: Option<&OmmaCell>,
+    ) -> Result<u32, OmmaErr> {
+        let window = self.window(source)?;
+        let region = window.copy_region(0, 0, window.width(), window.height());
+        Ok(self.window(target)?.paste_region(x, y, &region, key))
+    }
+
+    /// activate_tab shows the page at index of a tab container and hides every other page
+    pub fn activate_tab(&mut self, window_id: u32, index: usize) -> Result<(), OmmaErr> {
+        let Some(tabs) = self.window(window_id)?.tabs_mut() else {
+            return Err(OmmaErr::new(&format!(
+                "window {} is not a tab container",
+                window_id
+            )));
+        };
+        let pages = tabs.pages();
+        if index >= pages.len() {
+            return Err(OmmaErr::new(&format!(
+                "invalid tab {}, container {} has {} pages",
+                index,
+                window_id,
+                pages.len()
+            )));
+        }
+        tabs.set_active(index);
+        for (page_index, page) in pages.into_iter().enumerate() {
+            let window = self.window(page)?;
+            if page_index == index {
+                window.clear_hidden();
+            } else {
+                window.set_hidden();
+            }
+        }
+        Ok(())
+    }
+
+    /// tab_key switches the pages of a tab container on tab or the digits 1 through 9, returns
+    /// the newly active page
+    pub fn tab_key(&mut self, window_id: u32, key: char) -> Result<Option<usize>, OmmaErr> {
+        let target = self
+            .window(window_id)?
+            .tabs()
+            .and_then(|tabs| tabs.key_target(key));
+        if let Some(index) = target {
+            self.activate_tab(window_id, index)?;
+        }
+        Ok(target)
+    }
+
+    /// click activates the tab under a mouse press at screen position x, y, returns the tab
+    /// container and its newly active page
+    pub fn click(&mut self, x: usize, y: usize) -> Result<Option<(u32, usize)>, OmmaErr> {
+        let hit = self.windows[0].tab_at(&self.windows, 0, 0, None, x, y);
+        if let Some((window_id, index)) = hit {
+            self.activate_tab(window_id, index)?;
+        }
+        Ok(hit)
+    }
+
+    /// retire_object detaches an object from its window so it is no longer drawn. Object ids are
+    /// never reused. Retiring an object that is already detached does nothing.
+    pub fn retire_object(&mut self, object_id: u32) -> Result<(), OmmaErr> {
+        let window = self.object(object_id)?.window_id();
+        if !self.window(window)?.objects().contains(&object_id) {
+            return Ok(());
+        }
+        self.window(window)?.remove_object(object_id)?;
+        self.object(object_id)?.release();
+        Ok(())
+    }
+
     /// new_object creates a new ObjectBuilder object for further building
     pub fn new_object(&self) -> ObjectBuilder {
         ObjectBuilder::new()
@@ -117,9 +249,120 @@ impl Session {
         self.objects.push(object);
         let parent_window = self.window(window)?;
         parent_window.add_object(id);
+        self.refresh_groups();
         Ok(id)
     }
 
+    /// layered returns object ids sorted into drawing order, by layer and then by the order they
+    /// were added. Unknown ids are left out.
+    pub(crate) fn layered(objects: &[Object], ids: &[u32]) -> Vec<u32> {
+        let mut layered: Vec<(i32, u32)> = ids
+            .iter()
+            .filter_map(|&id| objects.get(id as usize).map(|object| (object.layer(), id)))
+            .collect();
+        layered.sort_by_key(|&(layer, _)| layer);
+        layered.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// object_at returns the topmost visible object of a window with an opaque cell at x, y of the
+    /// window's content
+    pub fn object_at(
+        &mut self,
+        window_id: u32,
+        x: usize,
+        y: usize,
+    ) -> Result<Option<u32>, OmmaErr> {
+        self.refresh_groups();
+        let ids = self.window(window_id)?.objects().to_vec();
+        for id in Self::layered(&self.objects, &ids).into_iter().rev() {
+            if self.object(id)?.hit(x, y) {
+                return Ok(Some(id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// set_group_hidden hides or shows every object in a named visibility group, including
+    /// objects added to the group later
+    pub fn set_group_hidden(&mut self, group: &str, hidden: bool) {
+        self.hidden_groups.retain(|name| name != group);
+        if hidden {
+            self.hidden_groups.push(group.to_string());
+        }
+        self.refresh_groups();
+    }
+
+    /// toggle_group flips the visibility of a named visibility group
+    pub fn toggle_group(&mut self, group: &str) {
+        let hidden = self.is_group_hidden(group);
+        self.set_group_hidden(group, !hidden);
+    }
+
+    /// add_object_group puts an object into a named visibility group
+    pub fn add_object_group(&mut self, object_id: u32, group: &str) -> Result<(), OmmaErr> {
+        self.object(object_id)?.add_group(group);
+        self.refresh_groups();
+        Ok(())
+    }
+
+    pub fn remove_object_group(&mut self, object_id: u32, group: &str) -> Result<(), OmmaErr> {
+        self.object(object_id)?.remove_group(group);
+        self.refresh_groups();
+        Ok(())
+    }
+
+    pub fn is_group_hidden(&self, group: &str) -> bool {
+        self.hidden_groups.iter().any(|name| name == group)
+    }
+
+    /// refresh_groups hands every object the visibility of its groups
+    fn refresh_groups(&mut self) {
+        for object in &mut self.objects {
+            let hidden = self
+                .hidden_groups
+                .iter()
+                .any(|group| object.in_group(group));
+            object.set_group_hidden(hidden);
+        }
+    }
+
+    /// flash_object overrides how an object is drawn for a while, such as a red tint when it takes
+    /// a hit, returns the effect id that ends the flash. The flash is a layer over the object's
+    /// style, so changes made to the style meanwhile show once it ends. Overlapping flashes show
+    /// the latest style until the last one ends or is cancelled.
+    pub fn flash_object(
+        &mut self,
+        object_id: u32,
+        style: &ObjectStyle,
+        duration: Duration,
+    ) -> Result<u32, OmmaErr> {
+        self.object(object_id)?.set_flash(Some(style));
+        let effect_id = self
+            .new_effect(EffectTarget::Detached)
+            .lifetime(duration)
+            .submit(self)?;
+        self.flashes.push((effect_id, object_id, style.clone()));
+        Ok(effect_id)
+    }
+
+    /// end_flash drops the flash run by effect_id, the object falls back to its latest remaining
+    /// flash or to its own style
+    fn end_flash(&mut self, effect_id: u32) {
+        let Some(index) = self.flashes.iter().position(|(id, _, _)| *id == effect_id) else {
+            return;
+        };
+        let (_, object_id, _) = self.flashes.remove(index);
+        let latest = self
+            .flashes
+            .iter()
+            .rev()
+            .find(|(_, id, _)| *id == object_id)
+            .map(|(_, _, style)| style.clone());
+        if let Ok(object) = self.object(object_id) {
+            object.set_flash(latest.as_ref());
+        }
+    }
+
     /// object returns the object corresponding to object_id if available
     pub fn object(&mut self, object_id: u32) -> Result<&mut Object, OmmaErr> {
         let id = object_id as usize;
@@ -134,6 +377,119 @@ impl Session {
         }
     }
 
+    /// object_handle returns the object corresponding to object_id along with the session, for
+    /// moves that are checked against its window and the other objects in it
+    pub fn object_handle(&mut self, object_id: u32) -> Result<ObjectHandle<'_>, OmmaErr> {
+        ObjectHandle::new(self, object_id)
+    }
+
+    pub(crate) fn objects(&self) -> &[Object] {
+        &self.objects
+    }
+
+    pub(crate) fn objects_mut(&mut self) -> &mut [Object] {
+        &mut self.objects
+    }
+
+    /// move_blocker returns what would stop an object from sitting at x, y of its window's content.
+    /// Passable objects neither block nor are blocked by other objects.
+    fn move_blocker(
+        &mut self,
+        object_id: u32,
+        x: usize,
+        y: usize,
+    ) -> Result<Option<MoveBlocker>, OmmaErr> {
+        let window_id = self.object(object_id)?.window_id();
+        self.window(window_id)?;
+        let mover = &self.objects[object_id as usize];
+        let window = &self.windows[window_id as usize];
+        if x + mover.width() > window.width() || y + mover.height() > window.height() {
+            return Ok(Some(MoveBlocker::Edge));
+        }
+        let Some(sprite) = mover.sprite() else {
+            return Ok(None);
+        };
+        let opaque: Vec<(usize, usize)> = (0..sprite.width())
+            .flat_map(|sx| (0..sprite.height()).map(move |sy| (sx, sy)))
+            .filter(|&(sx, sy)| sprite.is_opaque(sx, sy))
+            .map(|(sx, sy)| (x + sx, y + sy))
+            .collect();
+
+        if let Some(&(wall_x, wall_y)) = opaque.iter().find(|&&(cx, cy)| window.is_solid(cx, cy)) {
+            return Ok(Some(MoveBlocker::Wall(wall_x, wall_y)));
+        }
+        if mover.is_passable() {
+            return Ok(None);
+        }
+        for &other_id in window.objects() {
+            let Some(other) = self.objects.get(other_id as usize) else {
+                continue;
+            };
+            if other_id == object_id || other.is_passable() {
+                continue;
+            }
+            if opaque.iter().any(|&(cx, cy)| other.hit(cx, cy)) {
+                return Ok(Some(MoveBlocker::Object(other_id)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// try_place_object moves an object to x, y of its window's content unless something blocks
+    /// it, returns the blocker when the object stays put
+    pub fn try_place_object(
+        &mut self,
+        object_id: u32,
+        x: usize,
+        y: usize,
+    ) -> Result<Option<MoveBlocker>, OmmaErr> {
+        let blocker = self.move_blocker(object_id, x, y)?;
+        if blocker.is_none() {
+            self.object(object_id)?.set_offset(x, y);
+        }
+        Ok(blocker)
+    }
+
+    /// try_move_object moves an object by dx, dy within its window's content unless something
+    /// blocks it, returns the blocker when the object stays put
+    pub fn try_move_object(
+        &mut self,
+        object_id: u32,
+        dx: isize,
+        dy: isize,
+    ) -> Result<Option<MoveBlocker>, OmmaErr> {
+        let object = self.object(object_id)?;
+        let x = object.offset_x().checked_add_signed(dx);
+        let y = object.offset_y().checked_add_signed(dy);
+        match (x, y) {
+            (Some(x), Some(y)) => self.try_place_object(object_id, x, y),
+            _ => Ok(Some(MoveBlocker::Edge)),
+        }
+    }
+
+    /// move_object_to moves an object to x, y of its window's content, errors if it is blocked
+    pub fn move_object_to(&mut self, object_id: u32, x: usize, y: usize) -> Result<(), OmmaErr> {
+        match self.try_place_object(object_id, x, y)? {
+            None => Ok(()),
+            Some(blocker) => Err(OmmaErr::new(&format!(
+                "object {} can not move to {}:{}, blocked by {:?}",
+                object_id, x, y, blocker
+            ))),
+        }
+    }
+
+    /// move_object_by moves an object by dx, dy within its window's content, errors if it is
+    /// blocked
+    pub fn move_object_by(&mut self, object_id: u32, dx: isize, dy: isize) -> Result<(), OmmaErr> {
+        match self.try_move_object(object_id, dx, dy)? {
+            None => Ok(()),
+            Some(blocker) => Err(OmmaErr::new(&format!(
+                "object {} can not move by {}:{}, blocked by {:?}",
+                object_id, dx, dy, blocker
+            ))),
+        }
+    }
+
     /// object_direct returns the object corresponding to object_id if available
     pub fn object_direct(objects: &mut [Object], object_id: u32) -> Result<&mut Object, OmmaErr> {
         let id = object_id as usize;
@@ -148,23 +504,150 @@ impl Session {
         }
     }
 
-    /// render draws the current state of the session to the terminal
-    pub fn render(&mut self) -> Result<u32, OmmaErr> {
+    /// clock returns the session clock driving shaders
+    pub fn clock(&self) -> &OmmaClock {
+        &self.clock
+    }
+
+    /// set_clock replaces the session clock, such as with a fixed clock for reproducible frames
+    pub fn set_clock(&mut self, clock: OmmaClock) {
+        self.clock = clock;
+    }
+
+    /// tick advances the session clock by one frame, runs every effect and retires the targets of
+    /// expired ones. Returns the time since the previous tick.
+    pub fn tick(&mut self) -> Result<Duration, OmmaErr> {
+        let delta = self.clock.tick();
+        let frame = self.clock.frame();
+        let mut pending = std::mem::take(&mut self.effects).into_iter();
+        let mut kept = Vec::new();
+        while let Some(mut effect) = pending.next() {
+            if self.cancelled.contains(&effect.id()) {
+                continue;
+            }
+            let retired = match effect.step(self, delta, frame) {
+                Ok(true) => {
+                    kept.push(effect);
+                    continue;
+                }
+                Ok(false) => {
+                    self.end_flash(effect.id());
+                    effect.retire(self)
+                }
+                Err(e) => {
+                    // Keep the remaining effects so a failed tick can be retried
+                    kept.push(effect);
+                    Err(e)
+                }
+            };
+            if let Err(e) = retired {
+                kept.extend(pending);
+                self.finish_tick(kept);
+                return Err(e);
+            }
+        }
+        self.finish_tick(kept);
+        Ok(delta)
+    }
+
+    /// finish_tick restores the effects kept by a tick, along with any submitted by updates during
+    /// it, and drops the ones cancelled while it ran
+    fn finish_tick(&mut self, mut kept: Vec<Effect>) {
+        kept.append(&mut self.effects);
+        let cancelled = std::mem::take(&mut self.cancelled);
+        kept.retain(|effect| !cancelled.contains(&effect.id()));
+        self.effects = kept;
+    }
+
+    /// new_effect creates a new EffectBuilder for a temporary window or object
+    pub fn new_effect(&self, target: EffectTarget) -> EffectBuilder {
+        EffectBuilder::new(target)
+    }
+
+    /// register_effect adds an effect into the session
+    pub(crate) fn register_effect(&mut self, effect: Effect) {
+        self.effects.push(effect);
+    }
+
+    /// cancel_effect stops an effect without retiring its target, a cancelled flash ends at once.
+    /// It may be called from inside another effect's update
+    pub fn cancel_effect(&mut self, effect_id: u32) {
+        self.effects.retain(|effect| effect.id() != effect_id);
+        self.cancelled.push(effect_id);
+        self.end_flash(effect_id);
+    }
+
+    /// has_effect returns true while the effect is running
+    pub fn has_effect(&self, effect_id: u32) -> bool {
+        self.effects.iter().any(|effect| effect.id() == effect_id)
+            && !self.cancelled.contains(&effect_id)
+    }
+
+    /// effect_count returns the number of live effects
+    pub fn effect_count(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// set_theme sets the theme every window falls back to, windows with a theme of their own
+    /// override the roles it defines for themselves and their descendants
+    pub fn set_theme(&mut self, theme: &OmmaTheme) {
+        self.windows[0].set_theme(theme);
+    }
+
+    pub fn theme(&self) -> Option<&OmmaTheme> {
+        self.windows[0].theme()
+    }
+
+    pub fn clear_theme(&mut self) {
+        self.windows[0].clear_theme();
+    }
+
+    /// compose draws the current state of the session into the back plane without writing to the
+    /// terminal
+    pub fn compose(&mut self) -> Result<u32, OmmaErr> {
+        self.refresh_groups();
         let Self {
             term,
             objects,
             windows,
+            clock,
             ..
         } = self;
+        term.begin_frame();
         let window = &windows[0];
-        window.blit(windows, objects, term, 0, 0)?;
+        window.blit(windows, objects, term, clock, (0, 0), None)
+    }
+
+    /// render draws the current state of the session to the terminal
+    pub fn render(&mut self) -> Result<u32, OmmaErr> {
+        self.compose()?;
         self.term.render()
     }
 
-    /// read_key returns a single keypress from the terminal
+    /// cell_at returns the composed cell at a terminal position
+    pub fn cell_at(&self, x: usize, y: usize) -> Option<&OmmaCell> {
+        self.term.cell_at(x, y)
+    }
+
+    /// read_key returns a single keypress from the terminal, skipping mouse presses
     pub fn read_key(&mut self) -> Result<Option<char>, OmmaErr> {
         self.term.read_key()
     }
+
+    /// read_input returns a single keypress or mouse press from the terminal
+    pub fn read_input(&mut self) -> Result<Option<OmmaInput>, OmmaErr> {
+        self.term.read_input()
+    }
+
+    /// enable_mouse has the terminal report mouse presses to read_input, until disable_mouse or
+    /// the session ends
+    pub fn enable_mouse(&mut self) -> Result<(), OmmaErr> {
+        self.term.set_mouse(true)
+    }
+
+    pub fn disable_mouse(&mut self) -> Result<(), OmmaErr> {
+        self.term.set_mouse(false)
+    }
 }
 
 #[test]
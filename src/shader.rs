@@ -0,0 +1,112 @@
+use crate::cell::OmmaCell;
+use crate::color::OmmaColor;
+use std::time::Duration;
+
+/// ShaderCtx describes where and when a cell is being drawn
+#[derive(Debug, Clone, Copy)]
+pub struct ShaderCtx {
+    /// Position on the terminal
+    pub screen_x: usize,
+    pub screen_y: usize,
+    /// Position within whatever the shader is attached to: a window's content buffer, an object
+    /// or a string span
+    pub local_x: usize,
+    pub local_y: usize,
+    pub frame: u64,
+    pub elapsed: Duration,
+    pub window_id: u32,
+}
+
+/// OmmaShader maps a cell to a new cell at blit time. The cell stored in the window or object is
+/// left untouched, so shaders are free to change every frame.
+pub trait OmmaShader {
+    fn shade(&self, cell: &OmmaCell, ctx: &ShaderCtx) -> OmmaCell;
+}
+
+impl<F: Fn(&OmmaCell, &ShaderCtx) -> OmmaCell> OmmaShader for F {
+    fn shade(&self, cell: &OmmaCell, ctx: &ShaderCtx) -> OmmaCell {
+        self(cell, ctx)
+    }
+}
+
+/// RainbowShader cycles the foreground through color stops along x and over frames
+pub struct RainbowShader {
+    stops: Vec<OmmaColor>,
+    frames_per_step: u64,
+}
+
+impl RainbowShader {
+    /// new creates a rainbow moving one cell every frames_per_step frames, 0 holds it still
+    pub fn new(stops: &[OmmaColor], frames_per_step: u64) -> Self {
+        RainbowShader {
+            stops: stops.to_vec(),
+            frames_per_step,
+        }
+    }
+}
+
+impl OmmaShader for RainbowShader {
+    fn shade(&self, cell: &OmmaCell, ctx: &ShaderCtx) -> OmmaCell {
+        if self.stops.is_empty() {
+            return cell.clone();
+        }
+        let shift = ctx.frame.checked_div(self.frames_per_step).unwrap_or(0);
+        let index = (ctx.local_x as u64 + shift) % self.stops.len() as u64;
+        OmmaCell {
+            fg: Some(self.stops[index as usize]),
+            ..cell.clone()
+        }
+    }
+}
+
+/// PulseShader swells the foreground toward a color and back once per period, such as a sickly
+/// green on a poisoned unit
+pub struct PulseShader {
+    color: OmmaColor,
+    period: u64,
+}
+
+impl PulseShader {
+    /// new creates a pulse toward color lasting period frames
+    pub fn new(color: &OmmaColor, period: u64) -> Self {
+        PulseShader {
+            color: *color,
+            period: period.max(1),
+        }
+    }
+}
+
+impl OmmaShader for PulseShader {
+    fn shade(&self, cell: &OmmaCell, ctx: &ShaderCtx) -> OmmaCell {
+        let phase = (ctx.frame % self.period) as f64 / self.period as f64;
+        let amount = 1.0 - (phase * 2.0 - 1.0).abs();
+        let fg = match &cell.fg {
+            Some(fg) => fg.lerp(&self.color, amount),
+            None => self.color,
+        };
+        OmmaCell {
+            fg: Some(fg),
+            ..cell.clone()
+        }
+    }
+}
+
+#[test]
+fn rainbow() {
+    use crate::color::{BLUE, GREEN, RED};
+    let shader = RainbowShader::new(&[RED, GREEN, BLUE], 2);
+    let mut ctx = ShaderCtx {
+        screen_x: 0,
+        screen_y: 0,
+        local_x: 1,
+        local_y: 0,
+        frame: 0,
+        elapsed: Duration::ZERO,
+        window_id: 0,
+    };
+    let cell = shader.shade(&crate::cell::DEFAULT_CELL, &ctx);
+    assert!(cell.fg.map(|fg| fg.g) == Some(255));
+    ctx.frame = 2;
+    let cell = shader.shade(&crate::cell::DEFAULT_CELL, &ctx);
+    assert!(cell.fg.map(|fg| fg.b) == Some(255));
+}
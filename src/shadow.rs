@@ -0,0 +1,69 @@
+use crate::cell::{INHERIT, OVERLAY_CELL, OmmaCell};
+use crate::color::{BLACK, OmmaColor};
+
+/// OmmaShadow darkens the cells a window's footprint covers once moved by an offset, drawn under
+/// the window and over whatever was drawn before it
+#[derive(Clone, PartialEq)]
+pub struct OmmaShadow {
+    offset_x: isize,
+    offset_y: isize,
+    tint: OmmaColor,
+    amount: f64,
+    glyph: Option<char>,
+}
+
+impl Default for OmmaShadow {
+    /// Cells are twice as tall as they are wide, so a 2:1 offset looks square
+    fn default() -> Self {
+        Self::new(2, 1)
+    }
+}
+
+impl OmmaShadow {
+    /// new creates a half black shadow offset_x columns right and offset_y rows below the window,
+    /// negative offsets cast it left or up
+    pub fn new(offset_x: isize, offset_y: isize) -> Self {
+        Self {
+            offset_x,
+            offset_y,
+            tint: BLACK,
+            amount: 0.5,
+            glyph: None,
+        }
+    }
+
+    /// tint sets the color the shaded cells are mixed toward by an amount in 0.0..=1.0
+    pub fn tint(mut self, tint: &OmmaColor, amount: f64) -> Self {
+        self.tint = *tint;
+        self.amount = amount.clamp(0.0, 1.0);
+        self
+    }
+
+    /// glyph replaces the glyphs of the shaded cells instead of keeping the ones underneath
+    pub fn glyph(mut self, glyph: char) -> Self {
+        self.glyph = Some(glyph);
+        self
+    }
+
+    /// Getters
+    pub fn offset_x(&self) -> isize {
+        self.offset_x
+    }
+    pub fn offset_y(&self) -> isize {
+        self.offset_y
+    }
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    /// cell returns the overlay drawn over each shaded cell
+    pub fn cell(&self) -> OmmaCell {
+        let tint = Some(self.tint.with_alpha((self.amount * 255.0).round() as u8));
+        OmmaCell {
+            ch: self.glyph.unwrap_or(INHERIT),
+            fg: tint,
+            bg: tint,
+            ..OVERLAY_CELL
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::cell::{EMPTY, OmmaCell};
+use crate::error::OmmaErr;
+use crate::unicode;
+
+/// OmmaSprite is a rectangle of cells carried by an object. EMPTY cells are transparent and let
+/// whatever is underneath show through.
+#[derive(Clone)]
+pub struct OmmaSprite {
+    width: usize,
+    height: usize,
+    cells: Vec<Vec<OmmaCell>>,
+}
+
+impl OmmaSprite {
+    /// new creates a fully transparent width by height sprite
+    pub fn new(width: usize, height: usize) -> Self {
+        OmmaSprite {
+            width,
+            height,
+            cells: vec![vec![OmmaCell::transparent(); height]; width],
+        }
+    }
+
+    /// from_cell creates a single cell sprite
+    pub fn from_cell(cell: &OmmaCell) -> Self {
+        OmmaSprite {
+            width: 1,
+            height: 1,
+            cells: vec![vec![cell.clone()]],
+        }
+    }
+
+    /// from_cells creates a one row sprite from a styled string of cells
+    pub fn from_cells(cells: &[OmmaCell]) -> Self {
+        OmmaSprite {
+            width: cells.len(),
+            height: 1,
+            cells: cells.iter().map(|cell| vec![cell.clone()]).collect(),
+        }
+    }
+
+    /// from_str creates a one row sprite from text drawn with the properties of cell
+    pub fn from_str(text: &str, cell: &OmmaCell) -> Self {
+        Self::from_rows(&[text], cell, None)
+    }
+
+    /// from_rows creates a sprite from rows of text drawn with the properties of cell. Rows may
+    /// differ in width, the sprite is as wide as the widest and short rows are padded with
+    /// transparent cells. Characters matching transparent are transparent as well.
+    pub fn from_rows(rows: &[&str], cell: &OmmaCell, transparent: Option<char>) -> Self {
+        let rows: Vec<Vec<OmmaCell>> = rows
+            .iter()
+            .map(|row| unicode::text_cells(row, cell))
+            .collect();
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut sprite = Self::new(width, rows.len());
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, cell) in row.into_iter().enumerate() {
+                if Some(cell.ch) != transparent {
+                    sprite.cells[x][y] = cell;
+                }
+            }
+        }
+        sprite
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// get returns the cell at x, y or None outside the sprite
+    pub fn get(&self, x: usize, y: usize) -> Option<&OmmaCell> {
+        self.cells.get(x).and_then(|col| col.get(y))
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, cell: &OmmaCell) -> Result<(), OmmaErr> {
+        if x >= self.width || y >= self.height {
+            return Err(OmmaErr::new(&format!(
+                "invalid sprite write to {}:{} (size {}:{})",
+                x, y, self.width, self.height
+            )));
+        }
+        self.cells[x][y] = cell.clone();
+        Ok(())
+    }
+
+    /// is_opaque returns true if the cell at x, y exists and is not transparent
+    pub fn is_opaque(&self, x: usize, y: usize) -> bool {
+        self.get(x, y).is_some_and(|cell| cell.ch != EMPTY)
+    }
+
+    /// restyle runs f on every opaque cell, such as to recolor the whole sprite
+    pub fn restyle<F: FnMut(&mut OmmaCell)>(&mut self, mut f: F) {
+        for cell in self.cells.iter_mut().flatten() {
+            if cell.ch != EMPTY {
+                f(cell);
+            }
+        }
+    }
+}
+
+#[test]
+fn from_rows() {
+    let sprite = OmmaSprite::from_rows(&["/\\", "|.|"], &OmmaCell::default(), Some('.'));
+    assert!(sprite.width() == 3 && sprite.height() == 2);
+    assert!(sprite.get(0, 1).map(|cell| cell.ch) == Some('|'));
+    assert!(sprite.is_opaque(1, 0));
+    assert!(!sprite.is_opaque(2, 0));
+    assert!(!sprite.is_opaque(1, 1));
+    assert!(!sprite.is_opaque(3, 0));
+}
@@ -0,0 +1,471 @@
+use crate::cell::{
+    ATTR_BOLD, ATTR_REVERSE, BOX_CORNER_ALL_HOLLOW_CELL, BOX_HORIZ_HOLLOW_CELL,
+    BOX_VERT_HOLLOW_CELL, DEFAULT_CELL, OmmaCell,
+};
+use crate::error::OmmaErr;
+use crate::key::*;
+use crate::text::StyledText;
+use crate::theme::OmmaRole;
+use crate::window::Window;
+
+/// ColumnWidth controls how much horizontal space a column receives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnWidth {
+    /// Exactly this many columns
+    Fixed(usize),
+    /// A share of the space left after fixed and auto columns, weighted by the value
+    Ratio(usize),
+    /// As wide as the widest header or cell in the column
+    Auto,
+}
+
+/// TableEvent is the result of feeding a key into a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableEvent {
+    /// The key was not handled by the table
+    Ignored,
+    /// The selection moved to the contained row
+    Moved(usize),
+    /// The table scrolled horizontally
+    Scrolled,
+    /// The contained row was chosen
+    Chosen(usize),
+    /// The table was dismissed without a choice
+    Cancelled,
+}
+
+/// TableCell is a single entry of a table row, an optional style overrides the table's cell style.
+/// Styled text spans are drawn over the cell style.
+#[derive(Clone)]
+pub struct TableCell {
+    text: StyledText,
+    style: Option<OmmaCell>,
+}
+
+impl TableCell {
+    pub fn new(text: impl Into<StyledText>, style: Option<&OmmaCell>) -> Self {
+        TableCell {
+            text: text.into(),
+            style: style.cloned(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    pub fn styled_text(&self) -> &StyledText {
+        &self.text
+    }
+}
+
+impl From<&str> for TableCell {
+    fn from(text: &str) -> Self {
+        TableCell::new(text, None)
+    }
+}
+
+impl From<String> for TableCell {
+    fn from(text: String) -> Self {
+        TableCell::new(text, None)
+    }
+}
+
+impl From<StyledText> for TableCell {
+    fn from(text: StyledText) -> Self {
+        TableCell::new(text, None)
+    }
+}
+
+impl From<&OmmaCell> for TableCell {
+    fn from(cell: &OmmaCell) -> Self {
+        TableCell::new(cell.ch.to_string(), Some(cell))
+    }
+}
+
+#[derive(Clone)]
+struct Column {
+    header: StyledText,
+    width: ColumnWidth,
+}
+
+/// Table draws rows of cells into a window under an optional header row, with column separators,
+/// row selection and scrolling in both directions.
+#[derive(Clone)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<TableCell>>,
+    header: bool,
+    selected: usize,
+    scroll_x: usize,
+    max_scroll_x: usize,
+    scroll_y: usize,
+    cell: OmmaCell,
+    header_cell: OmmaCell,
+    highlight: OmmaCell,
+    separator: OmmaCell,
+    rule: OmmaCell,
+    junction: OmmaCell,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Table {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            header: true,
+            selected: 0,
+            scroll_x: 0,
+            max_scroll_x: 0,
+            scroll_y: 0,
+            cell: OmmaCell {
+                role: Some(OmmaRole::Text),
+                ..DEFAULT_CELL
+            },
+            header_cell: OmmaCell {
+                attrs: ATTR_BOLD,
+                role: Some(OmmaRole::Title),
+                ..DEFAULT_CELL
+            },
+            highlight: OmmaCell {
+                attrs: ATTR_REVERSE,
+                role: Some(OmmaRole::Selection),
+                ..DEFAULT_CELL
+            },
+            separator: OmmaCell {
+                role: Some(OmmaRole::Border),
+                ..BOX_VERT_HOLLOW_CELL
+            },
+            rule: OmmaCell {
+                role: Some(OmmaRole::Border),
+                ..BOX_HORIZ_HOLLOW_CELL
+            },
+            junction: OmmaCell {
+                role: Some(OmmaRole::Border),
+                ..BOX_CORNER_ALL_HOLLOW_CELL
+            },
+        }
+    }
+
+    /// column appends a column with a header label and sizing rule
+    pub fn column(mut self, header: impl Into<StyledText>, width: ColumnWidth) -> Self {
+        self.columns.push(Column {
+            header: header.into(),
+            width,
+        });
+        self
+    }
+
+    /// row appends a row, entries past the last column are ignored
+    pub fn row<I, C>(mut self, row: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<TableCell>,
+    {
+        self.push(row);
+        self
+    }
+
+    /// no_header hides the header row and the rule beneath it
+    pub fn no_header(mut self) -> Self {
+        self.header = false;
+        self
+    }
+
+    /// cell sets the style used for unselected rows
+    pub fn cell(mut self, cell: &OmmaCell) -> Self {
+        self.cell = cell.clone();
+        self
+    }
+
+    /// header_cell sets the style used for the header row
+    pub fn header_cell(mut self, cell: &OmmaCell) -> Self {
+        self.header_cell = cell.clone();
+        self
+    }
+
+    /// highlight sets the style used for the selected row
+    pub fn highlight(mut self, cell: &OmmaCell) -> Self {
+        self.highlight = cell.clone();
+        self
+    }
+
+    /// separators sets the vertical column separator, the header rule and their junction
+    pub fn separators(mut self, vert: &OmmaCell, horiz: &OmmaCell, junction: &OmmaCell) -> Self {
+        self.separator = vert.clone();
+        self.rule = horiz.clone();
+        self.junction = junction.clone();
+        self
+    }
+
+    /// push appends a row
+    pub fn push<I, C>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<TableCell>,
+    {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+    }
+
+    /// clear_rows removes every row and resets the selection and scroll
+    pub fn clear_rows(&mut self) {
+        self.rows.clear();
+        self.selected = 0;
+        self.scroll_x = 0;
+        self.scroll_y = 0;
+    }
+
+    pub fn rows(&self) -> &[Vec<TableCell>] {
+        &self.rows
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn scroll_x(&self) -> usize {
+        self.scroll_x
+    }
+
+    pub fn scroll_y(&self) -> usize {
+        self.scroll_y
+    }
+
+    /// select moves the selection to row
+    pub fn select(&mut self, row: usize) -> Result<(), OmmaErr> {
+        if row >= self.rows.len() {
+            return Err(OmmaErr::new(&format!(
+                "invalid table row {}, table has {} rows",
+                row,
+                self.rows.len()
+            )));
+        }
+        self.selected = row;
+        Ok(())
+    }
+
+    /// handle_key applies a keypress to the table and reports what happened
+    pub fn handle_key(&mut self, key: char) -> TableEvent {
+        match key {
+            KEY_UP | KEY_ARROW_UP if self.selected > 0 => {
+                self.selected -= 1;
+                TableEvent::Moved(self.selected)
+            }
+            KEY_DOWN | KEY_ARROW_DOWN if self.selected + 1 < self.rows.len() => {
+                self.selected += 1;
+                TableEvent::Moved(self.selected)
+            }
+            KEY_LEFT | KEY_ARROW_LEFT if self.scroll_x > 0 => {
+                self.scroll_x -= 1;
+                TableEvent::Scrolled
+            }
+            // Clamped against the width of the last draw
+            KEY_RIGHT | KEY_ARROW_RIGHT if self.scroll_x < self.max_scroll_x => {
+                self.scroll_x += 1;
+                TableEvent::Scrolled
+            }
+            KEY_ENTER | KEY_SPACE if !self.rows.is_empty() => TableEvent::Chosen(self.selected),
+            KEY_ESCAPE => TableEvent::Cancelled,
+            _ => TableEvent::Ignored,
+        }
+    }
+
+    /// column_widths resolves every column's width for a table drawn into cols columns
+    pub fn column_widths(&self, cols: usize) -> Vec<usize> {
+        let separators = self.columns.len().saturating_sub(1);
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| match column.width {
+                ColumnWidth::Fixed(width) => width,
+                ColumnWidth::Auto => self
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(index))
+                    .map(|cell| cell.text.width())
+                    .chain(std::iter::once(column.header.width()))
+                    .max()
+                    .unwrap_or(0),
+                ColumnWidth::Ratio(_) => 0,
+            })
+            .collect();
+
+        // Fixed and auto columns are cut short, left to right, when the table is too narrow
+        let mut remaining = cols.saturating_sub(separators);
+        for width in widths.iter_mut() {
+            *width = (*width).min(remaining);
+            remaining -= *width;
+        }
+        let weights: usize = self
+            .columns
+            .iter()
+            .map(|column| match column.width {
+                ColumnWidth::Ratio(weight) => weight,
+                _ => 0,
+            })
+            .sum();
+        let mut weight_left = weights;
+        for (index, column) in self.columns.iter().enumerate() {
+            if let ColumnWidth::Ratio(weight) = column.width {
+                // Every ratio column gets at least one column while there is space left
+                let share = (remaining * weight).checked_div(weight_left).unwrap_or(0);
+                widths[index] = share.max(1).min(remaining);
+                remaining -= widths[index];
+                weight_left -= weight;
+            }
+        }
+        widths
+    }
+
+    /// line lays a row of text out into cells using the resolved column widths
+    fn line(
+        &self,
+        texts: &[(&StyledText, &OmmaCell)],
+        widths: &[usize],
+        fill: &OmmaCell,
+    ) -> Vec<OmmaCell> {
+        let mut line = Vec::new();
+        for (index, &width) in widths.iter().enumerate() {
+            if index > 0 {
+                line.push(self.separator.clone());
+            }
+            let (mut cells, style) = match texts.get(index) {
+                Some((text, style)) => (text.clip_cells(style, width), *style),
+                None => (Vec::new(), fill),
+            };
+            cells.resize(
+                width,
+                OmmaCell {
+                    ch: ' ',
+                    ..style.clone()
+                },
+            );
+            line.extend(cells);
+        }
+        line
+    }
+
+    /// draw writes the visible part of the table into the content area of a window, returns the
+    /// number of cells written
+    pub fn draw(&mut self, window: &mut Window) -> Result<u32, OmmaErr> {
+        let cols = window.content_width();
+        let rows = window.content_height();
+        let widths = self.column_widths(cols);
+        let total_width = widths.iter().sum::<usize>() + widths.len().saturating_sub(1);
+        self.max_scroll_x = total_width.saturating_sub(cols);
+        self.scroll_x = self.scroll_x.min(self.max_scroll_x);
+
+        let mut lines: Vec<Vec<OmmaCell>> = Vec::new();
+        if self.header {
+            let headers: Vec<(&StyledText, &OmmaCell)> = self
+                .columns
+                .iter()
+                .map(|column| (&column.header, &self.header_cell))
+                .collect();
+            lines.push(self.line(&headers, &widths, &self.header_cell));
+            let mut rule = Vec::new();
+            for (index, &width) in widths.iter().enumerate() {
+                if index > 0 {
+                    rule.push(self.junction.clone());
+                }
+                rule.extend(std::iter::repeat_n(self.rule.clone(), width));
+            }
+            lines.push(rule);
+        }
+
+        let body_rows = rows.saturating_sub(lines.len());
+        if body_rows > 0 {
+            if self.selected < self.scroll_y {
+                self.scroll_y = self.selected;
+            } else if self.selected >= self.scroll_y + body_rows {
+                self.scroll_y = self.selected + 1 - body_rows;
+            }
+        }
+        self.scroll_y = self.scroll_y.min(self.rows.len().saturating_sub(body_rows));
+
+        for index in self.scroll_y..self.rows.len().min(self.scroll_y + body_rows) {
+            let style = if index == self.selected {
+                &self.highlight
+            } else {
+                &self.cell
+            };
+            let texts: Vec<(&StyledText, &OmmaCell)> = self.rows[index]
+                .iter()
+                .map(|cell| {
+                    // The selection highlight wins over per cell styles
+                    let cell_style = match &cell.style {
+                        Some(cell_style) if index != self.selected => cell_style,
+                        _ => style,
+                    };
+                    (&cell.text, cell_style)
+                })
+                .collect();
+            lines.push(self.line(&texts, &widths, style));
+        }
+
+        let blank = OmmaCell {
+            ch: ' ',
+            ..self.cell.clone()
+        };
+        let mut written = 0;
+        for y in 0..rows {
+            for x in 0..cols {
+                let cell = lines
+                    .get(y)
+                    .and_then(|line| line.get(x + self.scroll_x))
+                    .unwrap_or(&blank);
+                window.set_ommacell(x, y, cell)?;
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+}
+
+#[test]
+fn column_widths() {
+    let table = Table::new()
+        .column("Name", ColumnWidth::Auto)
+        .column("Qty", ColumnWidth::Fixed(3))
+        .column("Notes", ColumnWidth::Ratio(1))
+        .column("Value", ColumnWidth::Ratio(3))
+        .row(["Longsword", "1", "Rusty", "10"]);
+    // 9 + 3 + 3 separators leaves 15 columns split 1:3
+    assert!(table.column_widths(30) == vec![9, 3, 3, 12]);
+
+    // Narrower than its columns, the widths and separators never add up to more than cols
+    for cols in 0..20 {
+        let widths = table.column_widths(cols);
+        assert!(widths.iter().sum::<usize>() + 3 <= cols.max(3));
+    }
+    assert!(table.column_widths(9) == vec![6, 0, 0, 0]);
+    let ratios = Table::new()
+        .column("A", ColumnWidth::Ratio(1))
+        .column("B", ColumnWidth::Ratio(1))
+        .column("C", ColumnWidth::Ratio(1));
+    assert!(ratios.column_widths(3) == vec![1, 0, 0]);
+}
+
+#[test]
+fn navigation() {
+    let mut table = Table::new()
+        .column("Name", ColumnWidth::Auto)
+        .row(["Longsword"])
+        .row(["Dagger"]);
+    assert!(table.handle_key(KEY_ARROW_DOWN) == TableEvent::Moved(1));
+    assert!(table.handle_key(KEY_DOWN) == TableEvent::Ignored);
+    assert!(table.handle_key(KEY_ARROW_UP) == TableEvent::Moved(0));
+    assert!(table.handle_key(KEY_ARROW_RIGHT) == TableEvent::Ignored && table.scroll_x() == 0);
+    table.max_scroll_x = 1;
+    assert!(table.handle_key(KEY_ARROW_RIGHT) == TableEvent::Scrolled);
+    assert!(table.handle_key(KEY_RIGHT) == TableEvent::Ignored && table.scroll_x() == 1);
+    assert!(table.handle_key(KEY_ARROW_LEFT) == TableEvent::Scrolled && table.scroll_x() == 0);
+    assert!(table.handle_key(KEY_ESCAPE) == TableEvent::Cancelled);
+}
@@ -0,0 +1,173 @@
+use crate::cell::{ATTR_REVERSE, DEFAULT_CELL, EMPTY_CELL, OmmaCell};
+use crate::key::*;
+use crate::text::StyledText;
+use crate::theme::OmmaRole;
+
+#[derive(Clone)]
+struct Page {
+    title: StyledText,
+    window_id: u32,
+}
+
+/// Tabs turns a window into a container whose child windows are pages. Only the active page is
+/// visible, the rest are hidden, and a tab strip naming every page is drawn along the top border.
+/// Give it to WindowBuilder::tabs, then submit pages as children with WindowBuilder::page and
+/// switch between them through the Session.
+#[derive(Clone)]
+pub struct Tabs {
+    pages: Vec<Page>,
+    active: usize,
+    cell: OmmaCell,
+    highlight: OmmaCell,
+}
+
+impl Default for Tabs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tabs {
+    pub fn new() -> Self {
+        Tabs {
+            pages: Vec::new(),
+            active: 0,
+            cell: OmmaCell {
+                role: Some(OmmaRole::Title),
+                ..DEFAULT_CELL
+            },
+            highlight: OmmaCell {
+                attrs: ATTR_REVERSE,
+                role: Some(OmmaRole::Selection),
+                ..DEFAULT_CELL
+            },
+        }
+    }
+
+    /// cell sets the style used for inactive tab titles
+    pub fn cell(mut self, cell: &OmmaCell) -> Self {
+        self.cell = cell.clone();
+        self
+    }
+
+    /// highlight sets the style used for the active tab title
+    pub fn highlight(mut self, cell: &OmmaCell) -> Self {
+        self.highlight = cell.clone();
+        self
+    }
+
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// active_page returns the window id of the visible page
+    pub fn active_page(&self) -> Option<u32> {
+        self.pages.get(self.active).map(|page| page.window_id)
+    }
+
+    /// pages returns the window ids of every page in tab order
+    pub fn pages(&self) -> Vec<u32> {
+        self.pages.iter().map(|page| page.window_id).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// add_page appends a page, the first page added becomes active
+    pub(crate) fn add_page(&mut self, title: &StyledText, window_id: u32) {
+        self.pages.push(Page {
+            title: title.clone(),
+            window_id,
+        });
+    }
+
+    /// remove_page drops the page shown by window_id, keeping the active page where possible
+    pub(crate) fn remove_page(&mut self, window_id: u32) {
+        if let Some(index) = self
+            .pages
+            .iter()
+            .position(|page| page.window_id == window_id)
+        {
+            self.pages.remove(index);
+            if index < self.active || self.active >= self.pages.len() {
+                self.active = self.active.saturating_sub(1);
+            }
+        }
+    }
+
+    /// set_active marks the page at index as active without touching the page windows
+    pub(crate) fn set_active(&mut self, index: usize) {
+        self.active = index;
+    }
+
+    /// key_target returns the page a keypress switches to, tab cycles and the digits 1 through 9
+    /// pick a page
+    pub fn key_target(&self, key: char) -> Option<usize> {
+        let index = match key {
+            KEY_TAB if !self.pages.is_empty() => (self.active + 1) % self.pages.len(),
+            '1'..='9' => key as usize - '1' as usize,
+            _ => return None,
+        };
+        (index < self.pages.len()).then_some(index)
+    }
+
+    /// tab_at returns the tab under column x of the strip
+    pub fn tab_at(&self, x: usize) -> Option<usize> {
+        let mut start = 0;
+        for (index, page) in self.pages.iter().enumerate() {
+            let end = start + page.title.width() + 2;
+            if x >= start && x < end {
+                return Some(index);
+            }
+            start = end + 1;
+        }
+        None
+    }
+
+    /// strip lays out the tab titles, drawn over the container's top border
+    pub fn strip(&self) -> Vec<OmmaCell> {
+        let mut strip = Vec::new();
+        for (index, page) in self.pages.iter().enumerate() {
+            if index > 0 {
+                // Leave a gap between tabs so the border shows through
+                strip.push(EMPTY_CELL);
+            }
+            let mut cell = if index == self.active {
+                self.highlight.clone()
+            } else {
+                self.cell.clone()
+            };
+            cell.ch = ' ';
+            strip.push(cell.clone());
+            strip.extend(page.title.cells(&cell));
+            strip.push(cell);
+        }
+        strip
+    }
+}
+
+#[test]
+fn pages() {
+    let mut tabs = Tabs::new();
+    for (id, title) in [(4, "Stats"), (5, "Skills"), (6, "Equipment")] {
+        tabs.add_page(&StyledText::from(title), id);
+    }
+    assert!(tabs.key_target(KEY_TAB) == Some(1) && tabs.key_target('3') == Some(2));
+    assert!(tabs.key_target('4').is_none() && tabs.key_target('x').is_none());
+
+    // " Stats " takes columns 0 through 6, the gap after it belongs to no tab
+    assert!(tabs.tab_at(6) == Some(0) && tabs.tab_at(7).is_none() && tabs.tab_at(8) == Some(1));
+    assert!(tabs.strip().len() == 7 + 1 + 8 + 1 + 11);
+
+    // Removing a page before the active one keeps the same page active
+    tabs.set_active(2);
+    tabs.remove_page(4);
+    assert!(tabs.active_page() == Some(6) && tabs.pages() == vec![5, 6]);
+    tabs.remove_page(6);
+    assert!(tabs.active_page() == Some(5));
+}
@@ -1,8 +1,14 @@
-use crate::cell::{EMPTY, OmmaCell};
+use crate::cell::{CONTINUATION, EMPTY, INHERIT, OmmaCell};
 use crate::error::OmmaErr;
+use crate::filter::{FilterFrame, OmmaFilter};
+use crate::junction;
+use crate::key::{OmmaInput, decode_input};
+use crate::theme::{OmmaRole, OmmaTheme};
+use std::collections::VecDeque;
 use std::io::{self, IsTerminal, Read, Write};
 use std::mem::MaybeUninit;
 use std::os::fd::{AsRawFd, RawFd};
+use std::rc::Rc;
 
 type TcflagT = u32;
 type CcT = u8;
@@ -150,6 +156,16 @@ pub struct OmmaTerm {
     raw: RawMode,
     front: Vec<Vec<OmmaCell>>,
     back: Vec<Vec<OmmaCell>>,
+    borders: Vec<Vec<bool>>,
+    filters: Vec<FilterFrame>,
+    /// Themes of the windows being drawn, innermost last
+    themes: Vec<Rc<OmmaTheme>>,
+    /// The front plane does not match the screen and the next render redraws everything
+    stale: bool,
+    /// Input decoded from an earlier read and not yet returned
+    input: VecDeque<OmmaInput>,
+    /// The terminal reports mouse presses
+    mouse: bool,
 }
 
 impl std::fmt::Display for OmmaTerm {
@@ -160,6 +176,9 @@ impl std::fmt::Display for OmmaTerm {
 
 impl Drop for OmmaTerm {
     fn drop(&mut self) {
+        if self.mouse {
+            let _ = write!(self.stdout, "\x1b[?1006l\x1b[?1000l");
+        }
         let _ = write!(self.stdout, "\x1b[?25h");
         let _ = self.stdout.flush();
     }
@@ -171,6 +190,7 @@ impl OmmaTerm {
         let raw = RawMode::set_alt_raw()?;
         let front = vec![vec![OmmaCell::default(); max_row as usize]; max_col as usize];
         let back = vec![vec![OmmaCell::default(); max_row as usize]; max_col as usize];
+        let borders = vec![vec![false; max_row as usize]; max_col as usize];
         let mut stdout = io::stdout();
         eprintln!("Terminal dimensions ({max_row}:{max_col})");
         write!(stdout, "\x1b[?25l")?;
@@ -184,6 +204,12 @@ impl OmmaTerm {
             raw,
             front,
             back,
+            borders,
+            filters: Vec::new(),
+            themes: Vec::new(),
+            stale: true,
+            input: VecDeque::new(),
+            mouse: false,
         })
     }
 
@@ -193,6 +219,7 @@ impl OmmaTerm {
         let raw = RawMode::noop();
         let front = vec![vec![OmmaCell::default(); max_row as usize]; max_col as usize];
         let back = vec![vec![OmmaCell::default(); max_row as usize]; max_col as usize];
+        let borders = vec![vec![false; max_row as usize]; max_col as usize];
         Ok(OmmaTerm {
             row: 0,
             col: 0,
@@ -202,6 +229,12 @@ impl OmmaTerm {
             raw,
             front,
             back,
+            borders,
+            filters: Vec::new(),
+            themes: Vec::new(),
+            stale: true,
+            input: VecDeque::new(),
+            mouse: false,
         })
     }
 
@@ -240,13 +273,169 @@ impl OmmaTerm {
         }
         match cell.ch {
             EMPTY => Ok(0),
+            CONTINUATION => {
+                // The right half of a wide character is put along with its left half, a half
+                // whose left half is missing is drawn blank
+                if x > 0 && self.back[x - 1][y].is_wide() && self.back[x][y].ch == CONTINUATION {
+                    return Ok(0);
+                }
+                self.unpair(x, y);
+                self.back[x][y] = OmmaCell {
+                    ch: ' ',
+                    tail: None,
+                    ..self.back[x][y].clone()
+                };
+                self.borders[x][y] = false;
+                Ok(1)
+            }
             _ => {
-                self.back[x][y] = cell.clone();
+                let mut cell = match cell.role.and_then(|role| self.theme_style(role)) {
+                    Some(style) => OmmaTheme::restyle(style, cell),
+                    None => cell.clone(),
+                };
+                // Roles without a bg of their own sit on the theme's background
+                if cell.role.is_some() && cell.bg.is_none() {
+                    cell.bg = self
+                        .theme_style(OmmaRole::Background)
+                        .and_then(|background| background.bg);
+                }
+                // Innermost window's filters first, then each ancestor's
+                for frame in self.filters.iter().rev() {
+                    let local_x = x as isize - frame.origin_x;
+                    let local_y = y as isize - frame.origin_y;
+                    for filter in &frame.filters {
+                        cell = filter.apply(&cell, &self.back[x][y], local_x, local_y);
+                    }
+                }
+                if cell.is_composite() {
+                    let inherited = cell.ch == INHERIT;
+                    cell = cell.over(&self.back[x][y]);
+                    if inherited {
+                        // The glyph underneath stays, so does its pairing with a wide neighbor
+                        self.back[x][y] = cell;
+                        self.borders[x][y] = false;
+                        return Ok(1);
+                    }
+                }
+                self.unpair(x, y);
+                if cell.is_wide() {
+                    if x + 1 < x_max {
+                        self.unpair(x + 1, y);
+                        self.back[x + 1][y] = OmmaCell {
+                            ch: CONTINUATION,
+                            tail: None,
+                            ..cell.clone()
+                        };
+                        self.borders[x + 1][y] = false;
+                    } else {
+                        // A wide character does not fit in the last column
+                        cell.ch = ' ';
+                        cell.tail = None;
+                    }
+                }
+                self.back[x][y] = cell;
+                self.borders[x][y] = false;
                 Ok(1)
             }
         }
     }
 
+    /// unpair blanks the other half of a wide character about to be half overwritten at x, y
+    fn unpair(&mut self, x: usize, y: usize) {
+        let blank = |cell: &OmmaCell| OmmaCell {
+            ch: ' ',
+            tail: None,
+            ..cell.clone()
+        };
+        if self.back[x][y].ch == CONTINUATION && x > 0 && self.back[x - 1][y].is_wide() {
+            self.back[x - 1][y] = blank(&self.back[x - 1][y]);
+        }
+        if self.back[x][y].is_wide()
+            && self
+                .back
+                .get(x + 1)
+                .is_some_and(|col| col[y].ch == CONTINUATION)
+        {
+            self.back[x + 1][y] = blank(&self.back[x + 1][y]);
+        }
+    }
+
+    /// push_filters applies filters to every cell put until the matching pop_filters, origin is
+    /// the screen position filters see as 0:0
+    pub(crate) fn push_filters(
+        &mut self,
+        filters: Vec<OmmaFilter>,
+        origin_x: isize,
+        origin_y: isize,
+    ) {
+        self.filters.push(FilterFrame {
+            filters,
+            origin_x,
+            origin_y,
+        });
+    }
+
+    pub(crate) fn pop_filters(&mut self) {
+        self.filters.pop();
+    }
+
+    /// push_theme resolves roles through theme before the themes pushed earlier until the
+    /// matching pop_theme
+    pub(crate) fn push_theme(&mut self, theme: Rc<OmmaTheme>) {
+        self.themes.push(theme);
+    }
+
+    pub(crate) fn pop_theme(&mut self) {
+        self.themes.pop();
+    }
+
+    /// theme_style returns the style of role from the innermost theme defining it
+    fn theme_style(&self, role: OmmaRole) -> Option<&OmmaCell> {
+        self.themes.iter().rev().find_map(|theme| theme.get(role))
+    }
+
+    /// put_border_at sets the cell at a location like put_cell_at, but when a border drawn earlier
+    /// in the same frame is already there the two box drawing glyphs are merged into a junction
+    pub(crate) fn put_border_at(
+        &mut self,
+        x: usize,
+        y: usize,
+        cell: &OmmaCell,
+    ) -> Result<u32, OmmaErr> {
+        let under = self.back.get(x).and_then(|col| col.get(y)).map(|c| c.ch);
+        let merged = match under {
+            Some(under) if self.borders[x][y] => junction::merge(under, cell.ch),
+            _ => None,
+        };
+        let written = match merged {
+            Some(ch) => self.put_cell_at(x, y, &OmmaCell { ch, ..cell.clone() })?,
+            None => self.put_cell_at(x, y, cell)?,
+        };
+        if written > 0 {
+            self.borders[x][y] = true;
+        }
+        Ok(written)
+    }
+
+    /// begin_frame clears the back plane so retired windows leave nothing behind, forgets which
+    /// cells hold borders so junctions only form within a frame, and drops filters and themes left
+    /// behind by a frame that failed part way
+    pub(crate) fn begin_frame(&mut self) {
+        for col in &mut self.back {
+            col.fill(OmmaCell::default());
+        }
+        for col in &mut self.borders {
+            col.fill(false);
+        }
+        self.filters.clear();
+        self.themes.clear();
+    }
+
+    /// cell_at returns the back plane cell at a location
+    pub(crate) fn cell_at(&self, x: usize, y: usize) -> Option<&OmmaCell> {
+        self.back.get(x).and_then(|col| col.get(y))
+    }
+
     /// render_cell_at writes a cell to a location at the ommaterm saved stdout handle
     pub(crate) fn render_cell_at(
         &mut self,
@@ -255,31 +444,125 @@ impl OmmaTerm {
         cell: &OmmaCell,
     ) -> Result<(), OmmaErr> {
         self.move_cursor(x, y)?;
-        write!(self.stdout, "{}{}{}", cell.fgcode(), cell.bgcode(), cell.ch)?;
+        write!(
+            self.stdout,
+            "{}{}{}{}",
+            cell.attrcode(),
+            cell.fgcode(),
+            cell.bgcode(),
+            cell.glyph()
+        )?;
         Ok(())
     }
 
-    /// render writes the entire back plane terminal to the screen
+    /// dirty returns the cells of row y that differ between the back and front planes. Redrawing
+    /// either half of a wide character also redraws its other half, since terminals erase a wide
+    /// character whenever one of its columns is written.
+    fn dirty(&self, y: usize) -> Vec<bool> {
+        let cols = self.back.len();
+        let mut dirty: Vec<bool> = (0..cols)
+            .map(|x| self.stale || self.back[x][y] != self.front[x][y])
+            .collect();
+        for x in (1..cols).rev() {
+            let left_half =
+                self.back[x][y].ch == CONTINUATION || self.front[x][y].ch == CONTINUATION;
+            if dirty[x] && left_half {
+                dirty[x - 1] = true;
+            }
+        }
+        for x in 0..cols.saturating_sub(1) {
+            let right_half = self.back[x][y].is_wide() || self.front[x][y].is_wide();
+            if dirty[x] && right_half {
+                dirty[x + 1] = true;
+            }
+        }
+        dirty
+    }
+
+    /// render writes the cells that changed since the last render to the screen, returns the number
+    /// of cells written
     pub(crate) fn render(&mut self) -> Result<u32, OmmaErr> {
         let mut written = 0;
-        for x in 0..self.max_col {
-            for y in 0..self.max_row {
-                written += 1;
+        for y in 0..self.max_row {
+            let dirty = self.dirty(y as usize);
+            let mut x = 0;
+            while x < self.max_col {
                 let cell = self.back[x as usize][y as usize].clone();
+                if !dirty[x as usize] {
+                    x += 1;
+                    continue;
+                }
+                // A right half without its left half is drawn blank
+                let cell = if cell.ch == CONTINUATION {
+                    OmmaCell { ch: ' ', ..cell }
+                } else {
+                    cell
+                };
                 self.render_cell_at(x, y, &cell)?;
+                written += 1;
+                x += cell.width().max(1) as u16;
             }
         }
+        self.stdout.flush()?;
+        for (front, back) in self.front.iter_mut().zip(&self.back) {
+            front.clone_from(back);
+        }
+        self.stale = false;
         Ok(written)
     }
 
-    /// read_key reads a single keypress and returns the char
-    pub(crate) fn read_key(&mut self) -> Result<Option<char>, OmmaErr> {
-        let mut buf = [0u8; 1];
+    /// set_mouse turns reporting of mouse presses in SGR format on or off
+    pub(crate) fn set_mouse(&mut self, enabled: bool) -> Result<(), OmmaErr> {
+        let mode = if enabled { 'h' } else { 'l' };
+        write!(self.stdout, "\x1b[?1000{mode}\x1b[?1006{mode}")?;
+        self.stdout.flush()?;
+        self.mouse = enabled;
+        Ok(())
+    }
+
+    /// read_input reads a single keypress or mouse press. A terminal writes an escape sequence in
+    /// one go, so reading everything available keeps it together for decode_input.
+    pub(crate) fn read_input(&mut self) -> Result<Option<OmmaInput>, OmmaErr> {
+        if let Some(input) = self.input.pop_front() {
+            return Ok(Some(input));
+        }
+        let mut buf = [0u8; 32];
         let n = io::stdin().read(&mut buf)?;
-        if n == 0 {
-            Ok(None)
-        } else {
-            Ok(Some(buf[0] as char))
+        self.input.extend(decode_input(&buf[..n]));
+        Ok(self.input.pop_front())
+    }
+
+    /// read_key reads a single keypress and returns the char, mouse presses are skipped
+    pub(crate) fn read_key(&mut self) -> Result<Option<char>, OmmaErr> {
+        loop {
+            match self.read_input()? {
+                Some(OmmaInput::Key(key)) => return Ok(Some(key)),
+                Some(OmmaInput::Click(..)) => continue,
+                None => return Ok(None),
+            }
         }
     }
 }
+
+#[test]
+fn wide_cells() -> Result<(), OmmaErr> {
+    let mut term = OmmaTerm::new_mock(1, 4)?;
+    let wide = OmmaCell {
+        ch: '薬',
+        ..OmmaCell::default()
+    };
+    term.put_cell_at(1, 0, &wide)?;
+    assert!(term.back[2][0].ch == CONTINUATION);
+    term.front = term.back.clone();
+    term.stale = false;
+
+    // Overwriting the right half blanks the left half and both get redrawn
+    term.put_cell_at(2, 0, &OmmaCell::new('x', None, None, 0))?;
+    assert!(term.back[1][0].ch == ' ');
+    assert!(term.dirty(0) == [false, true, true, false]);
+
+    // A wide character does not fit in the last column
+    term.put_cell_at(3, 0, &wide)?;
+    assert!(term.back[3][0].ch == ' ');
+    Ok(())
+}
@@ -0,0 +1,299 @@
+//! Styled text made of spans that each carry their own colors and attributes. StyledText can be
+//! built span by span or parsed from inline markup:
+//!
+//! `"[green]Yes![/] [bold red on black]No![/]"`
+//!
+//! A tag holds space separated style words: a color name or hex color sets the foreground, `on`
+//! followed by a color sets the background, bold, dim, italic, underline, blink and reverse set
+//! attributes and a theme role name such as accent or danger draws the span in that role. Tags
+//! nest, `[/]` closes the innermost open tag and `[/name]` closes it only if it
+//! was opened as `[name]`. `[[` writes a literal `[`.
+
+use crate::cell::{
+    ATTR_BLINK, ATTR_BOLD, ATTR_DIM, ATTR_ITALIC, ATTR_REVERSE, ATTR_UNDERLINE, OmmaCell,
+};
+use crate::color::OmmaColor;
+use crate::error::OmmaErr;
+use crate::theme::OmmaRole;
+use crate::unicode;
+
+/// TextStyle is the look of a span. Colors and role left as None keep those of the cell the text
+/// is drawn with and attrs are added to the cell's attrs.
+#[derive(Clone, Default, PartialEq)]
+pub struct TextStyle {
+    pub fg: Option<OmmaColor>,
+    pub bg: Option<OmmaColor>,
+    pub attrs: u16,
+    pub role: Option<OmmaRole>,
+}
+
+impl TextStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fg(mut self, fg: &OmmaColor) -> Self {
+        self.fg = Some(*fg);
+        self
+    }
+
+    pub fn bg(mut self, bg: &OmmaColor) -> Self {
+        self.bg = Some(*bg);
+        self
+    }
+
+    pub fn attrs(mut self, attrs: u16) -> Self {
+        self.attrs |= attrs;
+        self
+    }
+
+    /// role draws the span in a theme role
+    pub fn role(mut self, role: OmmaRole) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    /// parse reads space separated style words, see the module documentation for the words
+    pub fn parse(words: &str) -> Result<Self, OmmaErr> {
+        let mut style = TextStyle::default();
+        let mut words = words.split_whitespace().peekable();
+        if words.peek().is_none() {
+            return Err(OmmaErr::new("missing style words"));
+        }
+        while let Some(word) = words.next() {
+            let attr = match word {
+                "bold" => ATTR_BOLD,
+                "dim" => ATTR_DIM,
+                "italic" => ATTR_ITALIC,
+                "underline" => ATTR_UNDERLINE,
+                "blink" => ATTR_BLINK,
+                "reverse" => ATTR_REVERSE,
+                _ => 0,
+            };
+            if attr != 0 {
+                style.attrs |= attr;
+            } else if word == "on" {
+                let Some(bg) = words.next().and_then(|word| word.parse().ok()) else {
+                    return Err(OmmaErr::new("expected a background color after \"on\""));
+                };
+                style.bg = Some(bg);
+            } else if let Some(role) = OmmaRole::from_name(word) {
+                style.role = Some(role);
+            } else if let Ok(fg) = word.parse() {
+                style.fg = Some(fg);
+            } else {
+                return Err(OmmaErr::new(&format!("unknown style \"{}\"", word)));
+            }
+        }
+        Ok(style)
+    }
+
+    /// apply returns cell drawn in the style
+    pub fn apply(&self, cell: &OmmaCell) -> OmmaCell {
+        OmmaCell {
+            fg: self.fg.or(cell.fg),
+            bg: self.bg.or(cell.bg),
+            attrs: cell.attrs | self.attrs,
+            role: self.role.or(cell.role),
+            ..cell.clone()
+        }
+    }
+
+    /// over layers the style on top of outer, such as a tag nested inside another
+    fn over(&self, outer: &TextStyle) -> TextStyle {
+        TextStyle {
+            fg: self.fg.or(outer.fg),
+            bg: self.bg.or(outer.bg),
+            attrs: outer.attrs | self.attrs,
+            role: self.role.or(outer.role),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct StyledSpan {
+    start: usize,
+    end: usize,
+    style: TextStyle,
+}
+
+/// StyledText is a string split into spans with their own style. Plain strings convert into
+/// StyledText with a single unstyled span.
+#[derive(Clone, Default, PartialEq)]
+pub struct StyledText {
+    text: String,
+    spans: Vec<StyledSpan>,
+}
+
+impl From<&str> for StyledText {
+    fn from(text: &str) -> Self {
+        StyledText::new().span(text, &TextStyle::default())
+    }
+}
+
+impl From<String> for StyledText {
+    fn from(text: String) -> Self {
+        StyledText::from(text.as_str())
+    }
+}
+
+impl From<&String> for StyledText {
+    fn from(text: &String) -> Self {
+        StyledText::from(text.as_str())
+    }
+}
+
+impl std::fmt::Display for StyledText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl StyledText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// span appends text drawn in style
+    pub fn span(mut self, text: &str, style: &TextStyle) -> Self {
+        self.push(text, style);
+        self
+    }
+
+    /// push appends text drawn in style, merging it into the last span if the styles match
+    pub fn push(&mut self, text: &str, style: &TextStyle) {
+        if text.is_empty() {
+            return;
+        }
+        let start = self.text.len();
+        self.text.push_str(text);
+        let end = self.text.len();
+        match self.spans.last_mut() {
+            Some(last) if &last.style == style => last.end = end,
+            _ => self.spans.push(StyledSpan {
+                start,
+                end,
+                style: style.clone(),
+            }),
+        }
+    }
+
+    /// parse builds styled text from inline markup, see the module documentation for the syntax
+    pub fn parse(markup: &str) -> Result<Self, OmmaErr> {
+        let mut styled = StyledText::new();
+        // Open tags with the style they resolve to, the bottom entry is the unstyled base
+        let mut open: Vec<(&str, TextStyle)> = vec![("", TextStyle::default())];
+        let mut rest = markup;
+        while let Some(index) = rest.find('[') {
+            let style = &open[open.len() - 1].1;
+            styled.push(&rest[..index], style);
+            rest = &rest[index + 1..];
+            if let Some(escaped) = rest.strip_prefix('[') {
+                styled.push("[", style);
+                rest = escaped;
+                continue;
+            }
+            let Some(close) = rest.find(']') else {
+                return Err(OmmaErr::new(&format!(
+                    "unclosed markup tag at byte {} of \"{}\"",
+                    markup.len() - rest.len() - 1,
+                    markup
+                )));
+            };
+            let tag = &rest[..close];
+            rest = &rest[close + 1..];
+            if let Some(name) = tag.strip_prefix('/') {
+                let name = name.trim();
+                match open.last() {
+                    Some((opened, _))
+                        if open.len() > 1 && (name.is_empty() || name == opened.trim()) =>
+                    {
+                        open.pop();
+                    }
+                    _ => {
+                        return Err(OmmaErr::new(&format!(
+                            "markup closing tag [{}] does not match an open tag in \"{}\"",
+                            tag, markup
+                        )));
+                    }
+                }
+            } else {
+                let style = TextStyle::parse(tag).map_err(|err| {
+                    OmmaErr::new(&format!("markup tag [{}]: {}", tag, err.body()))
+                })?;
+                let style = style.over(&open[open.len() - 1].1);
+                open.push((tag, style));
+            }
+        }
+        styled.push(rest, &open[open.len() - 1].1);
+        Ok(styled)
+    }
+
+    /// as_str returns the text without any styling
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// width returns the number of columns the text takes
+    pub fn width(&self) -> usize {
+        unicode::str_width(&self.text)
+    }
+
+    /// spans returns each span's text and style in order
+    pub fn spans(&self) -> impl Iterator<Item = (&str, &TextStyle)> {
+        self.spans
+            .iter()
+            .map(|span| (&self.text[span.start..span.end], &span.style))
+    }
+
+    /// cells lays the text out like unicode::text_cells with every span's style applied on top of
+    /// cell. A grapheme cluster takes the style of the span it starts in.
+    pub fn cells(&self, cell: &OmmaCell) -> Vec<OmmaCell> {
+        let mut cells = Vec::new();
+        let mut start = 0;
+        let mut spans = self.spans.iter().peekable();
+        for cluster in unicode::graphemes(&self.text) {
+            while spans.next_if(|span| span.end <= start).is_some() {}
+            let styled = match spans.peek() {
+                Some(span) if span.start <= start => span.style.apply(cell),
+                _ => cell.clone(),
+            };
+            cells.extend(unicode::text_cells(cluster, &styled));
+            start += cluster.len();
+        }
+        cells
+    }
+
+    /// clip_cells lays the text out like cells, cut short to at most width columns
+    pub fn clip_cells(&self, cell: &OmmaCell, width: usize) -> Vec<OmmaCell> {
+        unicode::clip(self.cells(cell), width)
+    }
+
+    /// fit_cells lays the text out like cells within width columns, text that does not fit is cut
+    /// short with an ellipsis
+    pub fn fit_cells(&self, cell: &OmmaCell, width: usize) -> Vec<OmmaCell> {
+        unicode::fit(self.cells(cell), cell, width)
+    }
+}
+
+#[test]
+fn parse() {
+    use crate::color::{GREEN, RED};
+    let text = StyledText::parse("[red]Yes![/] [bold on #000080]No [[[green]or][/][/]").unwrap();
+    assert!(text.as_str() == "Yes! No [or]");
+    let spans: Vec<(&str, &TextStyle)> = text.spans().collect();
+    assert!(spans.len() == 4);
+    assert!(spans[0].0 == "Yes!" && spans[0].1.fg == Some(RED));
+    assert!(spans[1].0 == " " && spans[1].1 == &TextStyle::default());
+    assert!(spans[2].0 == "No [" && spans[2].1.attrs == ATTR_BOLD);
+    assert!(spans[3].0 == "or]" && spans[3].1.fg == Some(GREEN));
+    assert!(spans[3].1.bg == Some(OmmaColor::new(0, 0, 128)) && spans[3].1.attrs == ATTR_BOLD);
+    assert!(StyledText::parse("[red]Yes[/blue]").is_err());
+    assert!(StyledText::parse("[/]").is_err());
+    assert!(StyledText::parse("[mauve]Yes").is_err());
+    assert!(StyledText::parse("[red").is_err());
+}
@@ -0,0 +1,192 @@
+//! Themes map semantic roles to cell styles. Cells, widgets and borders name the role they play
+//! and the theme of the nearest window up the tree that defines the role restyles them each time
+//! they are drawn, so switching a theme at runtime restyles everything beneath it.
+//!
+//! Theme files hold one role per line followed by `=` and the same style words markup tags use:
+//!
+//! ```text
+//! # Dusk
+//! background = on #1d1f21
+//! text = #c5c8c6
+//! title = bold #f0c674
+//! selection = black on #81a2be
+//! ```
+
+use crate::cell::OmmaCell;
+use crate::error::OmmaErr;
+use crate::text::TextStyle;
+use std::path::Path;
+
+/// OmmaRole names what a cell is for rather than how it looks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OmmaRole {
+    Background,
+    Border,
+    Title,
+    Text,
+    Accent,
+    Selection,
+    Warning,
+    Danger,
+    Disabled,
+}
+
+impl OmmaRole {
+    pub const ALL: [OmmaRole; 9] = [
+        OmmaRole::Background,
+        OmmaRole::Border,
+        OmmaRole::Title,
+        OmmaRole::Text,
+        OmmaRole::Accent,
+        OmmaRole::Selection,
+        OmmaRole::Warning,
+        OmmaRole::Danger,
+        OmmaRole::Disabled,
+    ];
+
+    /// name returns the lowercase name used by theme files and markup
+    pub fn name(&self) -> &'static str {
+        match self {
+            OmmaRole::Background => "background",
+            OmmaRole::Border => "border",
+            OmmaRole::Title => "title",
+            OmmaRole::Text => "text",
+            OmmaRole::Accent => "accent",
+            OmmaRole::Selection => "selection",
+            OmmaRole::Warning => "warning",
+            OmmaRole::Danger => "danger",
+            OmmaRole::Disabled => "disabled",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<OmmaRole> {
+        Self::ALL.into_iter().find(|role| role.name() == name)
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// OmmaTheme holds a style for some or all roles. A role's style adds its attrs to those of the
+/// cells drawn in it and replaces their fg and bg where the style sets them. Roles a theme leaves
+/// out fall through to the themes of ancestor windows, and cells whose role no theme defines are
+/// drawn as they are. Cells with a role but no bg take the background role's bg.
+#[derive(Clone, Default, PartialEq)]
+pub struct OmmaTheme {
+    styles: [Option<OmmaCell>; 9],
+}
+
+impl OmmaTheme {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// role sets the style of a role, only its fg, bg and attrs are used
+    pub fn role(mut self, role: OmmaRole, style: &OmmaCell) -> Self {
+        self.set_role(role, style);
+        self
+    }
+
+    pub fn set_role(&mut self, role: OmmaRole, style: &OmmaCell) {
+        self.styles[role.index()] = Some(style.clone());
+    }
+
+    pub fn remove_role(&mut self, role: OmmaRole) {
+        self.styles[role.index()] = None;
+    }
+
+    pub fn get(&self, role: OmmaRole) -> Option<&OmmaCell> {
+        self.styles[role.index()].as_ref()
+    }
+
+    /// restyle returns cell drawn in style, keeping the attributes the cell already has
+    pub(crate) fn restyle(style: &OmmaCell, cell: &OmmaCell) -> OmmaCell {
+        OmmaCell {
+            fg: style.fg.or(cell.fg),
+            bg: style.bg.or(cell.bg),
+            attrs: style.attrs | cell.attrs,
+            ..cell.clone()
+        }
+    }
+
+    /// parse reads a theme from the text format described in the module documentation. Blank
+    /// lines and lines starting with # are skipped.
+    pub fn parse(text: &str) -> Result<Self, OmmaErr> {
+        let mut theme = OmmaTheme::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: String| {
+                Err(OmmaErr::new(&format!(
+                    "theme line {}: {} in \"{}\"",
+                    index + 1,
+                    reason,
+                    line
+                )))
+            };
+            let Some((name, words)) = line.split_once('=') else {
+                return invalid("expected role = style".to_string());
+            };
+            let name = name.trim();
+            let Some(role) = OmmaRole::from_name(name) else {
+                return invalid(format!("unknown role \"{}\"", name));
+            };
+            if theme.get(role).is_some() {
+                return invalid(format!("role \"{}\" is set twice", name));
+            }
+            let style = match TextStyle::parse(words) {
+                Ok(style) if style.role.is_some() => {
+                    return invalid("a role can not be used as a style".to_string());
+                }
+                Ok(style) => style,
+                Err(err) => return invalid(err.body().to_string()),
+            };
+            theme.set_role(role, &style.apply(&OmmaCell::default()));
+        }
+        Ok(theme)
+    }
+
+    /// load reads a theme file, see parse
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, OmmaErr> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+}
+
+#[test]
+fn parse() {
+    use crate::cell::{ATTR_BOLD, ATTR_REVERSE};
+    use crate::color::{BLACK, OmmaColor};
+    let theme = OmmaTheme::parse(
+        "# Dusk\n\nbackground = on #1d1f21\n  title = bold #f0c674\nselection = reverse\n",
+    )
+    .unwrap();
+    let background = theme.get(OmmaRole::Background).unwrap();
+    assert!(background.fg.is_none() && background.bg == Some(OmmaColor::new(29, 31, 33)));
+    assert!(theme.get(OmmaRole::Title).unwrap().attrs == ATTR_BOLD);
+    assert!(theme.get(OmmaRole::Selection).unwrap().attrs == ATTR_REVERSE);
+    assert!(theme.get(OmmaRole::Danger).is_none());
+
+    let restyled = OmmaTheme::restyle(
+        theme.get(OmmaRole::Title).unwrap(),
+        &OmmaCell::new('T', None, Some(BLACK), ATTR_REVERSE),
+    );
+    assert!(
+        restyled.ch == 'T'
+            && restyled.bg == Some(BLACK)
+            && restyled.attrs == ATTR_BOLD | ATTR_REVERSE
+    );
+
+    for broken in [
+        "title bold",
+        "heading = bold",
+        "text = red\ntext = blue",
+        "text = mauve",
+        "text = accent",
+        "text =",
+    ] {
+        assert!(OmmaTheme::parse(broken).is_err());
+    }
+}
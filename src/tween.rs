@@ -0,0 +1,394 @@
+use crate::color::OmmaColor;
+use crate::effect::EffectTarget;
+use crate::error::OmmaErr;
+use crate::session::Session;
+use std::f64::consts::PI;
+use std::time::Duration;
+
+/// Easing shapes how a tween moves from its start to its end over its duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    /// Overshoots and springs back into place
+    ElasticOut,
+    /// Lands and bounces a few times before settling
+    BounceOut,
+}
+
+impl Easing {
+    /// apply maps linear progress t in 0.0..=1.0 onto the curve. Elastic curves leave 0.0..=1.0
+    /// part way through.
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t).powi(2),
+            Easing::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Easing::QuadInOut => 1.0 - (2.0 - 2.0 * t).powi(2) / 2.0,
+            Easing::CubicIn => t.powi(3),
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut if t < 0.5 => 4.0 * t.powi(3),
+            Easing::CubicInOut => 1.0 - (2.0 - 2.0 * t).powi(3) / 2.0,
+            Easing::ElasticOut if t == 0.0 || t == 1.0 => t,
+            Easing::ElasticOut => {
+                2f64.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+            }
+            Easing::BounceOut => {
+                let (n, d) = (7.5625, 2.75);
+                if t < 1.0 / d {
+                    n * t * t
+                } else if t < 2.0 / d {
+                    let t = t - 1.5 / d;
+                    n * t * t + 0.75
+                } else if t < 2.5 / d {
+                    let t = t - 2.25 / d;
+                    n * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / d;
+                    n * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+/// TweenTarget is the window or object property a tween animates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TweenTarget {
+    WindowOffset(u32),
+    /// The on screen size set by WindowBuilder::view
+    WindowView(u32),
+    WindowScroll(u32),
+    ObjectOffset(u32),
+    ObjectFg(u32),
+    ObjectBg(u32),
+}
+
+/// TweenValue is a position, size or scroll for the point targets and a color for the color ones
+#[derive(Clone)]
+pub enum TweenValue {
+    Point(usize, usize),
+    Color(OmmaColor),
+}
+
+impl TweenTarget {
+    /// read returns the current value of the property
+    fn read(&self, session: &mut Session) -> Result<TweenValue, OmmaErr> {
+        Ok(match *self {
+            TweenTarget::WindowOffset(id) => {
+                let window = session.window(id)?;
+                TweenValue::Point(window.offset_x(), window.offset_y())
+            }
+            TweenTarget::WindowView(id) => {
+                let window = session.window(id)?;
+                TweenValue::Point(window.view_width(), window.view_height())
+            }
+            TweenTarget::WindowScroll(id) => {
+                let window = session.window(id)?;
+                TweenValue::Point(window.scroll_x(), window.scroll_y())
+            }
+            TweenTarget::ObjectOffset(id) => {
+                let object = session.object(id)?;
+                TweenValue::Point(object.offset_x(), object.offset_y())
+            }
+            TweenTarget::ObjectFg(id) | TweenTarget::ObjectBg(id) => {
+                // Colors start from the object's style, or its first cell if the style sets none
+                let object = session.object(id)?;
+                let cell = object.cell().cloned().ok_or_else(|| {
+                    OmmaErr::new(&format!("object {} has no cell to animate", id))
+                })?;
+                let style = object.style().cloned().unwrap_or_default();
+                let color = match self {
+                    TweenTarget::ObjectFg(_) => style.fg.or(cell.fg),
+                    _ => style.bg.or(cell.bg),
+                };
+                TweenValue::Color(color.unwrap_or_default())
+            }
+        })
+    }
+
+    /// write sets the property, value must match the kind of the target
+    fn write(&self, session: &mut Session, value: &TweenValue) -> Result<(), OmmaErr> {
+        match (*self, value) {
+            (TweenTarget::WindowOffset(id), TweenValue::Point(x, y)) => {
+                session.window(id)?.set_offset(*x, *y)
+            }
+            (TweenTarget::WindowView(id), TweenValue::Point(x, y)) => {
+                session.window(id)?.set_view(*x, *y)
+            }
+            (TweenTarget::WindowScroll(id), TweenValue::Point(x, y)) => {
+                session.window(id)?.set_scroll(*x, *y)
+            }
+            (TweenTarget::ObjectOffset(id), TweenValue::Point(x, y)) => {
+                session.object(id)?.set_offset(*x, *y)
+            }
+            // Colors are written into the object's style so its cells keep their own colors
+            (TweenTarget::ObjectFg(id), TweenValue::Color(color)) => {
+                let object = session.object(id)?;
+                let mut style = object.style().cloned().unwrap_or_default();
+                style.fg = Some(*color);
+                object.set_style(&style);
+            }
+            (TweenTarget::ObjectBg(id), TweenValue::Color(color)) => {
+                let object = session.object(id)?;
+                let mut style = object.style().cloned().unwrap_or_default();
+                style.bg = Some(*color);
+                object.set_style(&style);
+            }
+            (target, _) => {
+                return Err(OmmaErr::new(&format!(
+                    "tween value does not match target {:?}",
+                    target
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// interpolate moves from towards to by eased progress t, points round to the nearest cell and
+/// stop at zero when an elastic curve overshoots
+fn interpolate(from: &TweenValue, to: &TweenValue, t: f64) -> TweenValue {
+    match (from, to) {
+        (TweenValue::Point(x0, y0), TweenValue::Point(x1, y1)) => {
+            let mix = |a: usize, b: usize| (a as f64 + (b as f64 - a as f64) * t).round().max(0.0);
+            TweenValue::Point(mix(*x0, *x1) as usize, mix(*y0, *y1) as usize)
+        }
+        (TweenValue::Color(from), TweenValue::Color(to)) => TweenValue::Color(from.lerp(to, t)),
+        _ => to.clone(),
+    }
+}
+
+/// TweenCallback runs once when a tween or animation completes
+pub type TweenCallback = Box<dyn FnOnce(&mut Session) -> Result<(), OmmaErr>>;
+
+/// Tween animates one property from wherever it is when the tween starts to a target value
+pub struct Tween {
+    target: TweenTarget,
+    to: TweenValue,
+    duration: Duration,
+    easing: Easing,
+    from: Option<TweenValue>,
+    on_complete: Option<TweenCallback>,
+}
+
+impl Tween {
+    /// new creates a linear tween of target to the value to over duration
+    pub fn new(target: TweenTarget, to: TweenValue, duration: Duration) -> Self {
+        Tween {
+            target,
+            to,
+            duration,
+            easing: Easing::Linear,
+            from: None,
+            on_complete: None,
+        }
+    }
+
+    pub fn window_offset(window_id: u32, x: usize, y: usize, duration: Duration) -> Self {
+        Self::new(
+            TweenTarget::WindowOffset(window_id),
+            TweenValue::Point(x, y),
+            duration,
+        )
+    }
+
+    pub fn window_view(window_id: u32, width: usize, height: usize, duration: Duration) -> Self {
+        Self::new(
+            TweenTarget::WindowView(window_id),
+            TweenValue::Point(width, height),
+            duration,
+        )
+    }
+
+    pub fn window_scroll(window_id: u32, x: usize, y: usize, duration: Duration) -> Self {
+        Self::new(
+            TweenTarget::WindowScroll(window_id),
+            TweenValue::Point(x, y),
+            duration,
+        )
+    }
+
+    pub fn object_offset(object_id: u32, x: usize, y: usize, duration: Duration) -> Self {
+        Self::new(
+            TweenTarget::ObjectOffset(object_id),
+            TweenValue::Point(x, y),
+            duration,
+        )
+    }
+
+    pub fn object_fg(object_id: u32, color: &OmmaColor, duration: Duration) -> Self {
+        Self::new(
+            TweenTarget::ObjectFg(object_id),
+            TweenValue::Color(*color),
+            duration,
+        )
+    }
+
+    pub fn object_bg(object_id: u32, color: &OmmaColor, duration: Duration) -> Self {
+        Self::new(
+            TweenTarget::ObjectBg(object_id),
+            TweenValue::Color(*color),
+            duration,
+        )
+    }
+
+    /// easing sets the curve the tween follows
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// on_complete sets a callback run once the tween reaches its end
+    pub fn on_complete<F>(mut self, on_complete: F) -> Self
+    where
+        F: FnOnce(&mut Session) -> Result<(), OmmaErr> + 'static,
+    {
+        self.on_complete = Some(Box::new(on_complete));
+        self
+    }
+
+    /// submit runs the tween on its own, returns the effect id used to cancel it
+    pub fn submit(self, session: &mut Session) -> Result<u32, OmmaErr> {
+        Animation::new().then(self).submit(session)
+    }
+
+    /// start captures the value the tween moves away from
+    fn start(&mut self, session: &mut Session) -> Result<(), OmmaErr> {
+        self.from = Some(self.target.read(session)?);
+        Ok(())
+    }
+
+    /// advance writes the property for elapsed time into the tween, returns true once it is done
+    fn advance(&mut self, session: &mut Session, elapsed: Duration) -> Result<bool, OmmaErr> {
+        let Some(from) = &self.from else {
+            return Ok(true);
+        };
+        let t = if self.duration.is_zero() {
+            1.0
+        } else {
+            (elapsed.as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+        };
+        let value = interpolate(from, &self.to, self.easing.apply(t));
+        self.target.write(session, &value)?;
+        if t < 1.0 {
+            return Ok(false);
+        }
+        self.from = None;
+        if let Some(on_complete) = self.on_complete.take() {
+            on_complete(session)?;
+        }
+        Ok(true)
+    }
+}
+
+/// Animation runs steps of tweens one after another, the tweens within a step run in parallel. It
+/// is driven by Session::tick as an effect and can be cancelled with Session::cancel_effect.
+#[derive(Default)]
+pub struct Animation {
+    steps: Vec<Vec<Tween>>,
+    on_complete: Option<TweenCallback>,
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Animation {
+            steps: Vec::new(),
+            on_complete: None,
+        }
+    }
+
+    /// then starts a new step with tween once every tween of the previous step has completed
+    pub fn then(mut self, tween: Tween) -> Self {
+        self.steps.push(vec![tween]);
+        self
+    }
+
+    /// with runs tween in parallel with the tweens of the current step
+    pub fn with(mut self, tween: Tween) -> Self {
+        match self.steps.last_mut() {
+            Some(step) => step.push(tween),
+            None => self.steps.push(vec![tween]),
+        }
+        self
+    }
+
+    /// on_complete sets a callback run once the last step completes
+    pub fn on_complete<F>(mut self, on_complete: F) -> Self
+    where
+        F: FnOnce(&mut Session) -> Result<(), OmmaErr> + 'static,
+    {
+        self.on_complete = Some(Box::new(on_complete));
+        self
+    }
+
+    /// submit starts the first step and adds the animation into the session, returns the effect
+    /// id used to cancel it
+    pub fn submit(mut self, session: &mut Session) -> Result<u32, OmmaErr> {
+        self.steps.reverse();
+        if let Some(step) = self.steps.last_mut() {
+            for tween in step {
+                tween.start(session)?;
+            }
+        }
+        let mut elapsed = Duration::ZERO;
+        session
+            .new_effect(EffectTarget::Detached)
+            .update(move |session, tick| {
+                elapsed += tick.delta;
+                while let Some(step) = self.steps.last_mut() {
+                    let mut done = true;
+                    for tween in step.iter_mut() {
+                        done &= tween.advance(session, elapsed)?;
+                    }
+                    if !done {
+                        return Ok(true);
+                    }
+                    // Carry time left over from the finished step into the next one
+                    let length = step.iter().map(|tween| tween.duration).max();
+                    elapsed = elapsed.saturating_sub(length.unwrap_or_default());
+                    self.steps.pop();
+                    if let Some(step) = self.steps.last_mut() {
+                        for tween in step {
+                            tween.start(session)?;
+                        }
+                    }
+                }
+                if let Some(on_complete) = self.on_complete.take() {
+                    on_complete(session)?;
+                }
+                Ok(false)
+            })
+            .submit(session)
+    }
+}
+
+#[test]
+fn easing() {
+    let easings = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::ElasticOut,
+        Easing::BounceOut,
+    ];
+    for easing in easings {
+        assert!(easing.apply(0.0).abs() < 1e-9);
+        assert!((easing.apply(1.0) - 1.0).abs() < 1e-9);
+    }
+    assert!(Easing::QuadIn.apply(0.5) == 0.25);
+    assert!(Easing::CubicInOut.apply(0.5) == 0.5);
+    assert!(Easing::ElasticOut.apply(0.2) > 1.0);
+}
@@ -0,0 +1,752 @@
+//! Display width and grapheme clusters. Terminals draw most characters one column wide, East Asian
+//! wide characters and emoji two columns wide and combining marks on top of the character before
+//! them. Text is split into grapheme clusters so a base character and everything attached to it
+//! lands in a single cell.
+
+use crate::cell::{CONTINUATION, OmmaCell};
+use std::rc::Rc;
+
+/// Code point ranges that take no column of their own: nonspacing and enclosing marks, format
+/// characters other than the soft hyphen, Hangul medial vowels and final consonants and
+/// emoji skin tones. Generated from the Unicode 14 character database, unassigned code points
+/// between ranges are folded into them.
+const ZERO_WIDTH: [(u32, u32); 315] = [
+    (0x0300, 0x036F),
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x05BF, 0x05BF),
+    (0x05C1, 0x05C2),
+    (0x05C4, 0x05C5),
+    (0x05C7, 0x05C7),
+    (0x0600, 0x0605),
+    (0x0610, 0x061A),
+    (0x061C, 0x061C),
+    (0x064B, 0x065F),
+    (0x0670, 0x0670),
+    (0x06D6, 0x06DD),
+    (0x06DF, 0x06E4),
+    (0x06E7, 0x06E8),
+    (0x06EA, 0x06ED),
+    (0x070F, 0x070F),
+    (0x0711, 0x0711),
+    (0x0730, 0x074A),
+    (0x07A6, 0x07B0),
+    (0x07EB, 0x07F3),
+    (0x07FD, 0x07FD),
+    (0x0816, 0x0819),
+    (0x081B, 0x0823),
+    (0x0825, 0x0827),
+    (0x0829, 0x082D),
+    (0x0859, 0x085B),
+    (0x0890, 0x089F),
+    (0x08CA, 0x0902),
+    (0x093A, 0x093A),
+    (0x093C, 0x093C),
+    (0x0941, 0x0948),
+    (0x094D, 0x094D),
+    (0x0951, 0x0957),
+    (0x0962, 0x0963),
+    (0x0981, 0x0981),
+    (0x09BC, 0x09BC),
+    (0x09C1, 0x09C4),
+    (0x09CD, 0x09CD),
+    (0x09E2, 0x09E3),
+    (0x09FE, 0x0A02),
+    (0x0A3C, 0x0A3C),
+    (0x0A41, 0x0A51),
+    (0x0A70, 0x0A71),
+    (0x0A75, 0x0A75),
+    (0x0A81, 0x0A82),
+    (0x0ABC, 0x0ABC),
+    (0x0AC1, 0x0AC8),
+    (0x0ACD, 0x0ACD),
+    (0x0AE2, 0x0AE3),
+    (0x0AFA, 0x0B01),
+    (0x0B3C, 0x0B3C),
+    (0x0B3F, 0x0B3F),
+    (0x0B41, 0x0B44),
+    (0x0B4D, 0x0B56),
+    (0x0B62, 0x0B63),
+    (0x0B82, 0x0B82),
+    (0x0BC0, 0x0BC0),
+    (0x0BCD, 0x0BCD),
+    (0x0C00, 0x0C00),
+    (0x0C04, 0x0C04),
+    (0x0C3C, 0x0C3C),
+    (0x0C3E, 0x0C40),
+    (0x0C46, 0x0C56),
+    (0x0C62, 0x0C63),
+    (0x0C81, 0x0C81),
+    (0x0CBC, 0x0CBC),
+    (0x0CBF, 0x0CBF),
+    (0x0CC6, 0x0CC6),
+    (0x0CCC, 0x0CCD),
+    (0x0CE2, 0x0CE3),
+    (0x0D00, 0x0D01),
+    (0x0D3B, 0x0D3C),
+    (0x0D41, 0x0D44),
+    (0x0D4D, 0x0D4D),
+    (0x0D62, 0x0D63),
+    (0x0D81, 0x0D81),
+    (0x0DCA, 0x0DCA),
+    (0x0DD2, 0x0DD6),
+    (0x0E31, 0x0E31),
+    (0x0E34, 0x0E3A),
+    (0x0E47, 0x0E4E),
+    (0x0EB1, 0x0EB1),
+    (0x0EB4, 0x0EBC),
+    (0x0EC8, 0x0ECD),
+    (0x0F18, 0x0F19),
+    (0x0F35, 0x0F35),
+    (0x0F37, 0x0F37),
+    (0x0F39, 0x0F39),
+    (0x0F71, 0x0F7E),
+    (0x0F80, 0x0F84),
+    (0x0F86, 0x0F87),
+    (0x0F8D, 0x0FBC),
+    (0x0FC6, 0x0FC6),
+    (0x102D, 0x1030),
+    (0x1032, 0x1037),
+    (0x1039, 0x103A),
+    (0x103D, 0x103E),
+    (0x1058, 0x1059),
+    (0x105E, 0x1060),
+    (0x1071, 0x1074),
+    (0x1082, 0x1082),
+    (0x1085, 0x1086),
+    (0x108D, 0x108D),
+    (0x109D, 0x109D),
+    (0x1160, 0x11FF),
+    (0x135D, 0x135F),
+    (0x1712, 0x1714),
+    (0x1732, 0x1733),
+    (0x1752, 0x1753),
+    (0x1772, 0x1773),
+    (0x17B4, 0x17B5),
+    (0x17B7, 0x17BD),
+    (0x17C6, 0x17C6),
+    (0x17C9, 0x17D3),
+    (0x17DD, 0x17DD),
+    (0x180B, 0x180F),
+    (0x1885, 0x1886),
+    (0x18A9, 0x18A9),
+    (0x1920, 0x1922),
+    (0x1927, 0x1928),
+    (0x1932, 0x1932),
+    (0x1939, 0x193B),
+    (0x1A17, 0x1A18),
+    (0x1A1B, 0x1A1B),
+    (0x1A56, 0x1A56),
+    (0x1A58, 0x1A60),
+    (0x1A62, 0x1A62),
+    (0x1A65, 0x1A6C),
+    (0x1A73, 0x1A7F),
+    (0x1AB0, 0x1B03),
+    (0x1B34, 0x1B34),
+    (0x1B36, 0x1B3A),
+    (0x1B3C, 0x1B3C),
+    (0x1B42, 0x1B42),
+    (0x1B6B, 0x1B73),
+    (0x1B80, 0x1B81),
+    (0x1BA2, 0x1BA5),
+    (0x1BA8, 0x1BA9),
+    (0x1BAB, 0x1BAD),
+    (0x1BE6, 0x1BE6),
+    (0x1BE8, 0x1BE9),
+    (0x1BED, 0x1BED),
+    (0x1BEF, 0x1BF1),
+    (0x1C2C, 0x1C33),
+    (0x1C36, 0x1C37),
+    (0x1CD0, 0x1CD2),
+    (0x1CD4, 0x1CE0),
+    (0x1CE2, 0x1CE8),
+    (0x1CED, 0x1CED),
+    (0x1CF4, 0x1CF4),
+    (0x1CF8, 0x1CF9),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F),
+    (0x2028, 0x202E),
+    (0x2060, 0x206F),
+    (0x20D0, 0x20FF),
+    (0x2CEF, 0x2CF1),
+    (0x2D7F, 0x2D7F),
+    (0x2DE0, 0x2DFF),
+    (0x302A, 0x302D),
+    (0x3099, 0x309A),
+    (0xA66F, 0xA672),
+    (0xA674, 0xA67D),
+    (0xA69E, 0xA69F),
+    (0xA6F0, 0xA6F1),
+    (0xA802, 0xA802),
+    (0xA806, 0xA806),
+    (0xA80B, 0xA80B),
+    (0xA825, 0xA826),
+    (0xA82C, 0xA82C),
+    (0xA8C4, 0xA8C5),
+    (0xA8E0, 0xA8F1),
+    (0xA8FF, 0xA8FF),
+    (0xA926, 0xA92D),
+    (0xA947, 0xA951),
+    (0xA980, 0xA982),
+    (0xA9B3, 0xA9B3),
+    (0xA9B6, 0xA9B9),
+    (0xA9BC, 0xA9BD),
+    (0xA9E5, 0xA9E5),
+    (0xAA29, 0xAA2E),
+    (0xAA31, 0xAA32),
+    (0xAA35, 0xAA36),
+    (0xAA43, 0xAA43),
+    (0xAA4C, 0xAA4C),
+    (0xAA7C, 0xAA7C),
+    (0xAAB0, 0xAAB0),
+    (0xAAB2, 0xAAB4),
+    (0xAAB7, 0xAAB8),
+    (0xAABE, 0xAABF),
+    (0xAAC1, 0xAAC1),
+    (0xAAEC, 0xAAED),
+    (0xAAF6, 0xAAF6),
+    (0xABE5, 0xABE5),
+    (0xABE8, 0xABE8),
+    (0xABED, 0xABED),
+    (0xD7B0, 0xD7FF),
+    (0xFB1E, 0xFB1E),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0xFEFF, 0xFEFF),
+    (0xFFF9, 0xFFFB),
+    (0x101FD, 0x101FD),
+    (0x102E0, 0x102E0),
+    (0x10376, 0x1037A),
+    (0x10A01, 0x10A0F),
+    (0x10A38, 0x10A3F),
+    (0x10AE5, 0x10AE6),
+    (0x10D24, 0x10D27),
+    (0x10EAB, 0x10EAC),
+    (0x10F46, 0x10F50),
+    (0x10F82, 0x10F85),
+    (0x11001, 0x11001),
+    (0x11038, 0x11046),
+    (0x11070, 0x11070),
+    (0x11073, 0x11074),
+    (0x1107F, 0x11081),
+    (0x110B3, 0x110B6),
+    (0x110B9, 0x110BA),
+    (0x110BD, 0x110BD),
+    (0x110C2, 0x110CD),
+    (0x11100, 0x11102),
+    (0x11127, 0x1112B),
+    (0x1112D, 0x11134),
+    (0x11173, 0x11173),
+    (0x11180, 0x11181),
+    (0x111B6, 0x111BE),
+    (0x111C9, 0x111CC),
+    (0x111CF, 0x111CF),
+    (0x1122F, 0x11231),
+    (0x11234, 0x11234),
+    (0x11236, 0x11237),
+    (0x1123E, 0x1123E),
+    (0x112DF, 0x112DF),
+    (0x112E3, 0x112EA),
+    (0x11300, 0x11301),
+    (0x1133B, 0x1133C),
+    (0x11340, 0x11340),
+    (0x11366, 0x11374),
+    (0x11438, 0x1143F),
+    (0x11442, 0x11444),
+    (0x11446, 0x11446),
+    (0x1145E, 0x1145E),
+    (0x114B3, 0x114B8),
+    (0x114BA, 0x114BA),
+    (0x114BF, 0x114C0),
+    (0x114C2, 0x114C3),
+    (0x115B2, 0x115B5),
+    (0x115BC, 0x115BD),
+    (0x115BF, 0x115C0),
+    (0x115DC, 0x115DD),
+    (0x11633, 0x1163A),
+    (0x1163D, 0x1163D),
+    (0x1163F, 0x11640),
+    (0x116AB, 0x116AB),
+    (0x116AD, 0x116AD),
+    (0x116B0, 0x116B5),
+    (0x116B7, 0x116B7),
+    (0x1171D, 0x1171F),
+    (0x11722, 0x11725),
+    (0x11727, 0x1172B),
+    (0x1182F, 0x11837),
+    (0x11839, 0x1183A),
+    (0x1193B, 0x1193C),
+    (0x1193E, 0x1193E),
+    (0x11943, 0x11943),
+    (0x119D4, 0x119DB),
+    (0x119E0, 0x119E0),
+    (0x11A01, 0x11A0A),
+    (0x11A33, 0x11A38),
+    (0x11A3B, 0x11A3E),
+    (0x11A47, 0x11A47),
+    (0x11A51, 0x11A56),
+    (0x11A59, 0x11A5B),
+    (0x11A8A, 0x11A96),
+    (0x11A98, 0x11A99),
+    (0x11C30, 0x11C3D),
+    (0x11C3F, 0x11C3F),
+    (0x11C92, 0x11CA7),
+    (0x11CAA, 0x11CB0),
+    (0x11CB2, 0x11CB3),
+    (0x11CB5, 0x11CB6),
+    (0x11D31, 0x11D45),
+    (0x11D47, 0x11D47),
+    (0x11D90, 0x11D91),
+    (0x11D95, 0x11D95),
+    (0x11D97, 0x11D97),
+    (0x11EF3, 0x11EF4),
+    (0x13430, 0x13438),
+    (0x16AF0, 0x16AF4),
+    (0x16B30, 0x16B36),
+    (0x16F4F, 0x16F4F),
+    (0x16F8F, 0x16F92),
+    (0x16FE4, 0x16FE4),
+    (0x1BC9D, 0x1BC9E),
+    (0x1BCA0, 0x1CF46),
+    (0x1D167, 0x1D169),
+    (0x1D173, 0x1D182),
+    (0x1D185, 0x1D18B),
+    (0x1D1AA, 0x1D1AD),
+    (0x1D242, 0x1D244),
+    (0x1DA00, 0x1DA36),
+    (0x1DA3B, 0x1DA6C),
+    (0x1DA75, 0x1DA75),
+    (0x1DA84, 0x1DA84),
+    (0x1DA9B, 0x1DAAF),
+    (0x1E000, 0x1E02A),
+    (0x1E130, 0x1E136),
+    (0x1E2AE, 0x1E2AE),
+    (0x1E2EC, 0x1E2EF),
+    (0x1E8D0, 0x1E8D6),
+    (0x1E944, 0x1E94A),
+    (0x1F3FB, 0x1F3FF),
+    (0xE0000, 0xE0FFF),
+];
+
+/// Code point ranges of spacing marks, which take a column like any other character but attach to
+/// the character before them in a grapheme cluster, such as Devanagari vowel signs. Generated the
+/// same way as ZERO_WIDTH.
+const SPACING_MARK: [(u32, u32); 166] = [
+    (0x0903, 0x0903),
+    (0x093B, 0x093B),
+    (0x093E, 0x0940),
+    (0x0949, 0x094C),
+    (0x094E, 0x094F),
+    (0x0982, 0x0983),
+    (0x09BE, 0x09C0),
+    (0x09C7, 0x09CC),
+    (0x09D7, 0x09D7),
+    (0x0A03, 0x0A03),
+    (0x0A3E, 0x0A40),
+    (0x0A83, 0x0A83),
+    (0x0ABE, 0x0AC0),
+    (0x0AC9, 0x0ACC),
+    (0x0B02, 0x0B03),
+    (0x0B3E, 0x0B3E),
+    (0x0B40, 0x0B40),
+    (0x0B47, 0x0B4C),
+    (0x0B57, 0x0B57),
+    (0x0BBE, 0x0BBF),
+    (0x0BC1, 0x0BCC),
+    (0x0BD7, 0x0BD7),
+    (0x0C01, 0x0C03),
+    (0x0C41, 0x0C44),
+    (0x0C82, 0x0C83),
+    (0x0CBE, 0x0CBE),
+    (0x0CC0, 0x0CC4),
+    (0x0CC7, 0x0CCB),
+    (0x0CD5, 0x0CD6),
+    (0x0D02, 0x0D03),
+    (0x0D3E, 0x0D40),
+    (0x0D46, 0x0D4C),
+    (0x0D57, 0x0D57),
+    (0x0D82, 0x0D83),
+    (0x0DCF, 0x0DD1),
+    (0x0DD8, 0x0DDF),
+    (0x0DF2, 0x0DF3),
+    (0x0F3E, 0x0F3F),
+    (0x0F7F, 0x0F7F),
+    (0x102B, 0x102C),
+    (0x1031, 0x1031),
+    (0x1038, 0x1038),
+    (0x103B, 0x103C),
+    (0x1056, 0x1057),
+    (0x1062, 0x1064),
+    (0x1067, 0x106D),
+    (0x1083, 0x1084),
+    (0x1087, 0x108C),
+    (0x108F, 0x108F),
+    (0x109A, 0x109C),
+    (0x1715, 0x1715),
+    (0x1734, 0x1734),
+    (0x17B6, 0x17B6),
+    (0x17BE, 0x17C5),
+    (0x17C7, 0x17C8),
+    (0x1923, 0x1926),
+    (0x1929, 0x1931),
+    (0x1933, 0x1938),
+    (0x1A19, 0x1A1A),
+    (0x1A55, 0x1A55),
+    (0x1A57, 0x1A57),
+    (0x1A61, 0x1A61),
+    (0x1A63, 0x1A64),
+    (0x1A6D, 0x1A72),
+    (0x1B04, 0x1B04),
+    (0x1B35, 0x1B35),
+    (0x1B3B, 0x1B3B),
+    (0x1B3D, 0x1B41),
+    (0x1B43, 0x1B44),
+    (0x1B82, 0x1B82),
+    (0x1BA1, 0x1BA1),
+    (0x1BA6, 0x1BA7),
+    (0x1BAA, 0x1BAA),
+    (0x1BE7, 0x1BE7),
+    (0x1BEA, 0x1BEC),
+    (0x1BEE, 0x1BEE),
+    (0x1BF2, 0x1BF3),
+    (0x1C24, 0x1C2B),
+    (0x1C34, 0x1C35),
+    (0x1CE1, 0x1CE1),
+    (0x1CF7, 0x1CF7),
+    (0x302E, 0x302F),
+    (0xA823, 0xA824),
+    (0xA827, 0xA827),
+    (0xA880, 0xA881),
+    (0xA8B4, 0xA8C3),
+    (0xA952, 0xA953),
+    (0xA983, 0xA983),
+    (0xA9B4, 0xA9B5),
+    (0xA9BA, 0xA9BB),
+    (0xA9BE, 0xA9C0),
+    (0xAA2F, 0xAA30),
+    (0xAA33, 0xAA34),
+    (0xAA4D, 0xAA4D),
+    (0xAA7B, 0xAA7B),
+    (0xAA7D, 0xAA7D),
+    (0xAAEB, 0xAAEB),
+    (0xAAEE, 0xAAEF),
+    (0xAAF5, 0xAAF5),
+    (0xABE3, 0xABE4),
+    (0xABE6, 0xABE7),
+    (0xABE9, 0xABEA),
+    (0xABEC, 0xABEC),
+    (0x11000, 0x11000),
+    (0x11002, 0x11002),
+    (0x11082, 0x11082),
+    (0x110B0, 0x110B2),
+    (0x110B7, 0x110B8),
+    (0x1112C, 0x1112C),
+    (0x11145, 0x11146),
+    (0x11182, 0x11182),
+    (0x111B3, 0x111B5),
+    (0x111BF, 0x111C0),
+    (0x111CE, 0x111CE),
+    (0x1122C, 0x1122E),
+    (0x11232, 0x11233),
+    (0x11235, 0x11235),
+    (0x112E0, 0x112E2),
+    (0x11302, 0x11303),
+    (0x1133E, 0x1133F),
+    (0x11341, 0x1134D),
+    (0x11357, 0x11357),
+    (0x11362, 0x11363),
+    (0x11435, 0x11437),
+    (0x11440, 0x11441),
+    (0x11445, 0x11445),
+    (0x114B0, 0x114B2),
+    (0x114B9, 0x114B9),
+    (0x114BB, 0x114BE),
+    (0x114C1, 0x114C1),
+    (0x115AF, 0x115B1),
+    (0x115B8, 0x115BB),
+    (0x115BE, 0x115BE),
+    (0x11630, 0x11632),
+    (0x1163B, 0x1163C),
+    (0x1163E, 0x1163E),
+    (0x116AC, 0x116AC),
+    (0x116AE, 0x116AF),
+    (0x116B6, 0x116B6),
+    (0x11720, 0x11721),
+    (0x11726, 0x11726),
+    (0x1182C, 0x1182E),
+    (0x11838, 0x11838),
+    (0x11930, 0x11938),
+    (0x1193D, 0x1193D),
+    (0x11940, 0x11940),
+    (0x11942, 0x11942),
+    (0x119D1, 0x119D3),
+    (0x119DC, 0x119DF),
+    (0x119E4, 0x119E4),
+    (0x11A39, 0x11A39),
+    (0x11A57, 0x11A58),
+    (0x11A97, 0x11A97),
+    (0x11C2F, 0x11C2F),
+    (0x11C3E, 0x11C3E),
+    (0x11CA9, 0x11CA9),
+    (0x11CB1, 0x11CB1),
+    (0x11CB4, 0x11CB4),
+    (0x11D8A, 0x11D8E),
+    (0x11D93, 0x11D94),
+    (0x11D96, 0x11D96),
+    (0x11EF5, 0x11EF6),
+    (0x16F51, 0x16F87),
+    (0x16FF0, 0x16FF1),
+    (0x1D165, 0x1D166),
+    (0x1D16D, 0x1D172),
+];
+
+/// Code point ranges drawn two columns wide, the Wide and Fullwidth classes of EastAsianWidth.txt
+/// from Unicode 14 plus the planes reserved for ideographs
+const WIDE: [(u32, u32); 61] = [
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x2329, 0x232A),
+    (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0),
+    (0x23F3, 0x23F3),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x267F, 0x267F),
+    (0x2693, 0x2693),
+    (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26C4, 0x26C5),
+    (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4),
+    (0x26EA, 0x26EA),
+    (0x26F2, 0x26F3),
+    (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA),
+    (0x26FD, 0x26FD),
+    (0x2705, 0x2705),
+    (0x270A, 0x270B),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x274E, 0x274E),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27B0, 0x27B0),
+    (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x2E80, 0x3029),
+    (0x3030, 0x303E),
+    (0x3041, 0x3098),
+    (0x309B, 0x3247),
+    (0x3250, 0x4DBF),
+    (0x4E00, 0xA4CF),
+    (0xA960, 0xA97F),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x16FE0, 0x16FE3),
+    (0x17000, 0x1B2FF),
+    (0x1F004, 0x1F004),
+    (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F200, 0x1F3FA),
+    (0x1F400, 0x1F64F),
+    (0x1F680, 0x1F6FF),
+    (0x1F7E0, 0x1F7F0),
+    (0x1F900, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x3FFFD),
+];
+
+const ZWJ: char = '\u{200D}';
+const VS16: char = '\u{FE0F}';
+
+fn in_table(table: &[(u32, u32)], ch: char) -> bool {
+    let code = ch as u32;
+    table
+        .binary_search_by(|&(start, end)| {
+            if end < code {
+                std::cmp::Ordering::Less
+            } else if start > code {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+fn is_regional_indicator(ch: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&ch)
+}
+
+/// char_width returns the number of columns a character takes on its own: 0, 1 or 2
+pub fn char_width(ch: char) -> usize {
+    if ch == ZWJ || ch.is_control() || in_table(&ZERO_WIDTH, ch) {
+        0
+    } else if in_table(&WIDE, ch) {
+        2
+    } else {
+        1
+    }
+}
+
+/// cluster_width returns the number of columns a grapheme cluster takes. Emoji presentation
+/// selectors and flag pairs widen a cluster to two columns.
+pub fn cluster_width(cluster: &str) -> usize {
+    let mut chars = cluster.chars();
+    let Some(first) = chars.next() else {
+        return 0;
+    };
+    if cluster.contains(VS16) || (is_regional_indicator(first) && chars.next().is_some()) {
+        return 2;
+    }
+    cluster.chars().map(char_width).max().unwrap_or(0).min(2)
+}
+
+/// str_width returns the number of columns text takes
+pub fn str_width(text: &str) -> usize {
+    graphemes(text).map(cluster_width).sum()
+}
+
+/// graphemes splits text into grapheme clusters: a base character followed by its combining and
+/// spacing marks, variation selectors and skin tones, joined emoji sequences and flag pairs
+pub fn graphemes(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let mut chars = rest.char_indices();
+        let (_, first) = chars.next()?;
+        let mut prev = first;
+        let mut flags = usize::from(is_regional_indicator(first));
+        let mut end = rest.len();
+        for (index, ch) in chars {
+            let joins = char_width(ch) == 0 && !ch.is_control()
+                || in_table(&SPACING_MARK, ch)
+                || prev == ZWJ
+                || (prev == '\r' && ch == '\n')
+                || (flags == 1 && is_regional_indicator(ch));
+            if !joins {
+                end = index;
+                break;
+            }
+            if is_regional_indicator(ch) {
+                flags += 1;
+            }
+            prev = ch;
+        }
+        let (cluster, tail) = rest.split_at(end);
+        rest = tail;
+        Some(cluster)
+    })
+}
+
+/// text_cells lays text out as cells drawn with the properties of cell. Each grapheme cluster
+/// becomes a cell holding its first character with the rest kept in the cell's tail, and wide
+/// clusters are followed by a CONTINUATION cell. Clusters without width are dropped.
+pub fn text_cells(text: &str, cell: &OmmaCell) -> Vec<OmmaCell> {
+    let mut cells = Vec::new();
+    for cluster in graphemes(text) {
+        let width = cluster_width(cluster);
+        if width == 0 {
+            continue;
+        }
+        let mut chars = cluster.chars();
+        let ch = chars.next().unwrap_or(' ');
+        let tail = chars.as_str();
+        cells.push(OmmaCell {
+            ch,
+            tail: (!tail.is_empty()).then(|| Rc::from(tail)),
+            ..cell.clone()
+        });
+        if width == 2 {
+            cells.push(OmmaCell {
+                ch: CONTINUATION,
+                tail: None,
+                ..cell.clone()
+            });
+        }
+    }
+    cells
+}
+
+/// clip_cells lays text out like text_cells, cut short to at most width columns without splitting
+/// a wide character
+pub fn clip_cells(text: &str, cell: &OmmaCell, width: usize) -> Vec<OmmaCell> {
+    clip(text_cells(text, cell), width)
+}
+
+/// fit_cells lays text out like text_cells within width columns, text that does not fit is cut
+/// short with an ellipsis
+pub fn fit_cells(text: &str, cell: &OmmaCell, width: usize) -> Vec<OmmaCell> {
+    fit(text_cells(text, cell), cell, width)
+}
+
+/// clip cuts laid out cells short to at most width columns without splitting a wide character
+pub(crate) fn clip(mut cells: Vec<OmmaCell>, width: usize) -> Vec<OmmaCell> {
+    if cells.len() > width {
+        cells.truncate(width);
+        if cells.last().is_some_and(|cell| cell.is_wide()) {
+            cells.pop();
+        }
+    }
+    cells
+}
+
+/// fit cuts laid out cells short to width columns ending in an ellipsis drawn with the properties
+/// of cell, cells that fit are left alone
+pub(crate) fn fit(cells: Vec<OmmaCell>, cell: &OmmaCell, width: usize) -> Vec<OmmaCell> {
+    if cells.len() <= width {
+        return cells;
+    }
+    let mut cells = clip(cells, width.saturating_sub(1));
+    if width > 0 {
+        cells.push(OmmaCell {
+            ch: '…',
+            tail: None,
+            ..cell.clone()
+        });
+    }
+    cells
+}
+
+#[test]
+fn widths() {
+    assert!(str_width("Potion") == 6);
+    assert!(str_width("回復薬") == 6);
+    assert!(str_width("e\u{301}") == 1);
+    assert!(str_width("👍🏽") == 2);
+    assert!(str_width("🇯🇵") == 2);
+    assert!(str_width("❤\u{FE0F}") == 2);
+    // Emoji presentation symbols outside the emoji blocks
+    assert!(str_width("☔⚡") == 4);
+    // Devanagari vowel signs and the virama attach to the consonant before them, only the
+    // nonspacing ones are zero width on their own
+    assert!(str_width("कि") == 1 && str_width("क्ष") == 2);
+    assert!(char_width('\u{093F}') == 1 && char_width('\u{0903}') == 1);
+    assert!(char_width('\u{0941}') == 0 && char_width('\u{094D}') == 0);
+    assert!(graphemes("नमस्ते").count() == 4);
+    let clusters: Vec<&str> = graphemes("ne\u{301}👨\u{200D}👩🇯🇵!").collect();
+    assert!(clusters == ["n", "e\u{301}", "👨\u{200D}👩", "🇯🇵", "!"]);
+}
+
+#[test]
+fn cells() {
+    let cells = text_cells("薬e\u{301}", &OmmaCell::default());
+    assert!(cells.len() == 3);
+    assert!(cells[1].ch == CONTINUATION);
+    assert!(cells[2].ch == 'e' && cells[2].tail.as_deref() == Some("\u{301}"));
+    let glyphs: String = fit_cells("回復薬", &OmmaCell::default(), 4)
+        .iter()
+        .filter(|cell| cell.ch != CONTINUATION)
+        .map(|cell| cell.ch)
+        .collect();
+    assert!(glyphs == "回…");
+}
@@ -1,10 +1,57 @@
 use crate::border::OmmaBorder;
-use crate::cell::{BLANK_CELL, EMPTY_CELL, OmmaCell};
+use crate::canvas::{OmmaRegion, ellipse_rows, line_points};
+use crate::cell::{BLANK_CELL, EMPTY, EMPTY_CELL, OmmaCell};
+use crate::clock::OmmaClock;
 use crate::error::OmmaErr;
-use crate::object::Object;
+use crate::filter::OmmaFilter;
+use crate::label::OmmaLabel;
+use crate::object::{Object, ObjectView};
 use crate::pad::OmmaPad;
+use crate::pixel::{OmmaPixels, PixelMode};
+use crate::scrollbar::OmmaScrollbar;
 use crate::session::Session;
+use crate::shader::{OmmaShader, ShaderCtx};
+use crate::shadow::OmmaShadow;
+use crate::tabs::Tabs;
 use crate::term::OmmaTerm;
+use crate::text::StyledText;
+use crate::theme::{OmmaRole, OmmaTheme};
+use std::rc::Rc;
+
+/// ShaderSpan applies a shader to a run of cells on one row of the content buffer
+#[derive(Clone)]
+struct ShaderSpan {
+    x: usize,
+    y: usize,
+    len: usize,
+    shader: Rc<dyn OmmaShader>,
+}
+
+/// Clip is the part of the screen a window may draw into, as left, top, right and bottom with
+/// the right and bottom edges exclusive
+pub type Clip = (isize, isize, isize, isize);
+
+/// clip_point returns the screen position x, y if it lies inside clip and on the screen
+fn clip_point(clip: Option<Clip>, x: isize, y: isize) -> Option<(usize, usize)> {
+    let inside = clip.is_none_or(|(left, top, right, bottom)| {
+        (left..right).contains(&x) && (top..bottom).contains(&y)
+    });
+    (inside && x >= 0 && y >= 0).then_some((x as usize, y as usize))
+}
+
+/// clip_rect narrows clip to the width by height area at screen position x, y
+fn clip_rect(clip: Option<Clip>, x: isize, y: isize, width: usize, height: usize) -> Clip {
+    let (right, bottom) = (x + width as isize, y + height as isize);
+    match clip {
+        Some((left, top, clip_right, clip_bottom)) => (
+            left.max(x),
+            top.max(y),
+            clip_right.min(right),
+            clip_bottom.min(bottom),
+        ),
+        None => (x, y, right, bottom),
+    }
+}
 
 #[allow(dead_code)]
 #[derive(Default, Clone)]
@@ -24,9 +71,22 @@ pub struct Window {
     scroll_y: usize,
     border: Option<OmmaBorder>,
     pad: OmmaPad,
+    scrollbar_v: Option<OmmaScrollbar>,
+    scrollbar_h: Option<OmmaScrollbar>,
+    title: Option<OmmaLabel>,
+    footer: Option<OmmaLabel>,
+    filters: Vec<(OmmaFilter, bool)>,
+    shaders: Vec<Rc<dyn OmmaShader>>,
+    spans: Vec<ShaderSpan>,
+    theme: Option<Rc<OmmaTheme>>,
+    shadow: Option<OmmaShadow>,
+    pixels: Option<OmmaPixels>,
+    tabs: Option<Tabs>,
     hidden: bool,
     virt: bool,
     buffer: Vec<Vec<OmmaCell>>,
+    solid: Vec<Vec<bool>>,
+    top_strip: Vec<OmmaCell>,
 }
 
 pub struct WindowBuilder {
@@ -42,7 +102,20 @@ pub struct WindowBuilder {
     scroll_x: usize,
     scroll_y: usize,
     border: Option<OmmaBorder>,
+    border_sides: Option<(bool, bool, bool, bool)>,
+    border_unmerged: bool,
     pad: OmmaPad,
+    scrollbar_v: Option<OmmaScrollbar>,
+    scrollbar_h: Option<OmmaScrollbar>,
+    title: Option<OmmaLabel>,
+    footer: Option<OmmaLabel>,
+    filters: Vec<OmmaFilter>,
+    shaders: Vec<Rc<dyn OmmaShader>>,
+    theme: Option<Rc<OmmaTheme>>,
+    shadow: Option<OmmaShadow>,
+    pixels: Option<PixelMode>,
+    tabs: Option<Tabs>,
+    page: Option<StyledText>,
     hidden: bool,
     virt: bool,
     fill: Option<OmmaCell>,
@@ -63,7 +136,20 @@ impl WindowBuilder {
             scroll_x: 0,
             scroll_y: 0,
             border: None,
+            border_sides: None,
+            border_unmerged: false,
             pad: OmmaPad::default(),
+            scrollbar_v: None,
+            scrollbar_h: None,
+            title: None,
+            footer: None,
+            filters: Vec::new(),
+            shaders: Vec::new(),
+            theme: None,
+            shadow: None,
+            pixels: None,
+            tabs: None,
+            page: None,
             hidden: false,
             virt: false,
             fill: None,
@@ -77,6 +163,14 @@ impl WindowBuilder {
         self
     }
 
+    /// view sets the on screen size of the window, the content buffer keeps the size given to new
+    /// and is scrolled within the view
+    pub fn view(mut self, view_width: usize, view_height: usize) -> Self {
+        self.view_width = view_width;
+        self.view_height = view_height;
+        self
+    }
+
     /// parent sets the window that owns this window. Omitting parent implicitly sets the parent to
     /// the System Window.
     pub fn parent(mut self, parent_id: u32) -> Self {
@@ -129,6 +223,51 @@ impl WindowBuilder {
         self
     }
 
+    /// title sets a label drawn into the top border of the window
+    pub fn title(mut self, title: &OmmaLabel) -> Self {
+        self.title = Some(title.clone());
+        self
+    }
+
+    /// footer sets a label drawn into the bottom border of the window
+    pub fn footer(mut self, footer: &OmmaLabel) -> Self {
+        self.footer = Some(footer.clone());
+        self
+    }
+
+    /// border_unmerged stops the border from joining into junctions with borders beneath it. It
+    /// applies to the border the window is submitted with, whether set before or after.
+    pub fn border_unmerged(mut self) -> Self {
+        self.border_unmerged = true;
+        self
+    }
+
+    /// border_sides picks which edges of the border are drawn and take up space. It applies to the
+    /// border the window is submitted with, whether set before or after.
+    pub fn border_sides(mut self, top: bool, bottom: bool, left: bool, right: bool) -> Self {
+        self.border_sides = Some((top, bottom, left, right));
+        self
+    }
+
+    /// filter appends a filter applied to everything the window and its descendants draw
+    pub fn filter(mut self, filter: &OmmaFilter) -> Self {
+        self.filters.push(filter.clone());
+        self
+    }
+
+    /// shader appends a shader applied to the window's content cells at blit time
+    pub fn shader(mut self, shader: Rc<dyn OmmaShader>) -> Self {
+        self.shaders.push(shader);
+        self
+    }
+
+    /// theme styles the roles of cells drawn by the window and its descendants, roles the theme
+    /// leaves out are resolved through the themes of ancestors
+    pub fn theme(mut self, theme: &OmmaTheme) -> Self {
+        self.theme = Some(Rc::new(theme.clone()));
+        self
+    }
+
     /// pad sets a pad for the window
     pub fn pad(mut self, pad: &OmmaPad) -> Self {
         self.pad = pad.clone();
@@ -147,6 +286,43 @@ impl WindowBuilder {
         self
     }
 
+    /// shadow casts a shadow over whatever is drawn before the window, see OmmaShadow
+    pub fn shadow(mut self, shadow: &OmmaShadow) -> Self {
+        self.shadow = Some(shadow.clone());
+        self
+    }
+
+    /// pixels makes the window a pixel canvas covering its content buffer, see set_pixels
+    pub fn pixels(mut self, mode: PixelMode) -> Self {
+        self.pixels = Some(mode);
+        self
+    }
+
+    /// tabs makes the window a tab container, its children submitted with page are its pages
+    pub fn tabs(mut self, tabs: &Tabs) -> Self {
+        self.tabs = Some(tabs.clone());
+        self
+    }
+
+    /// page submits the window as a page of its parent tab container, named title in the tab
+    /// strip. The first page is shown and later pages start hidden.
+    pub fn page(mut self, title: impl Into<StyledText>) -> Self {
+        self.page = Some(title.into());
+        self
+    }
+
+    /// scrollbar_v draws a vertical scrollbar in the right border, or the right pad without one
+    pub fn scrollbar_v(mut self, scrollbar: &OmmaScrollbar) -> Self {
+        self.scrollbar_v = Some(scrollbar.clone());
+        self
+    }
+
+    /// scrollbar_h draws a horizontal scrollbar in the bottom border, or the bottom pad without one
+    pub fn scrollbar_h(mut self, scrollbar: &OmmaScrollbar) -> Self {
+        self.scrollbar_h = Some(scrollbar.clone());
+        self
+    }
+
     /// hidden marks a window as hidden and it will be skipped during rendering
     pub fn hidden(mut self) -> Self {
         self.hidden = true;
@@ -168,7 +344,29 @@ impl WindowBuilder {
 
     /// submit adds a WindowBuilder into the session as a new window, returns window id
     pub fn submit(&self, session: &mut Session) -> Result<u32, OmmaErr> {
+        // Pages after the first start hidden
+        let page_hidden = match &self.page {
+            Some(_) => match session.window(self.parent_id)?.tabs() {
+                Some(tabs) => !tabs.is_empty(),
+                None => {
+                    return Err(OmmaErr::new(&format!(
+                        "window {} is not a tab container",
+                        self.parent_id
+                    )));
+                }
+            },
+            None => false,
+        };
         let id = crate::next_window_id()?;
+        let mut border = self.border.clone();
+        if let Some(border) = &mut border {
+            if let Some((top, bottom, left, right)) = self.border_sides {
+                border.set_sides(top, bottom, left, right);
+            }
+            if self.border_unmerged {
+                border.set_unmerged();
+            }
+        }
         let buffer = vec![vec![OmmaCell::transparent(); self.height]; self.width];
         let name = if let Some(name) = &self.name {
             name
@@ -190,11 +388,26 @@ impl WindowBuilder {
             view_height: self.view_height,
             scroll_x: self.scroll_x,
             scroll_y: self.scroll_y,
-            border: self.border.clone(),
+            border,
             pad: self.pad.clone(),
-            hidden: self.hidden,
+            scrollbar_v: self.scrollbar_v.clone(),
+            scrollbar_h: self.scrollbar_h.clone(),
+            title: self.title.clone(),
+            footer: self.footer.clone(),
+            filters: self.filters.iter().map(|f| (f.clone(), true)).collect(),
+            shaders: self.shaders.clone(),
+            spans: Vec::new(),
+            theme: self.theme.clone(),
+            shadow: self.shadow.clone(),
+            pixels: self
+                .pixels
+                .map(|mode| OmmaPixels::new(mode, self.width, self.height)),
+            tabs: self.tabs.clone(),
+            hidden: self.hidden || page_hidden,
             virt: self.virt,
             buffer,
+            solid: Vec::new(),
+            top_strip: Vec::new(),
         };
 
         if let Some(fill) = &self.fill {
@@ -202,6 +415,11 @@ impl WindowBuilder {
         }
 
         let id = session.register_window(window)?;
+        if let Some(title) = &self.page
+            && let Some(tabs) = &mut session.window(self.parent_id)?.tabs
+        {
+            tabs.add_page(title, id);
+        }
 
         Ok(id)
     }
@@ -228,6 +446,14 @@ impl Window {
         self.parent_id
     }
 
+    pub fn children(&self) -> &[u32] {
+        &self.children
+    }
+
+    pub fn objects(&self) -> &[u32] {
+        &self.objects
+    }
+
     pub fn offset_x(&self) -> usize {
         self.offset_x
     }
@@ -236,6 +462,12 @@ impl Window {
         self.offset_y
     }
 
+    /// set_offset moves the window within its parent
+    pub fn set_offset(&mut self, offset_x: usize, offset_y: usize) {
+        self.offset_x = offset_x;
+        self.offset_y = offset_y;
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -252,6 +484,120 @@ impl Window {
         self.view_height
     }
 
+    /// set_view resizes the on screen size of the window and keeps the scroll inside the content
+    pub fn set_view(&mut self, view_width: usize, view_height: usize) {
+        self.view_width = view_width;
+        self.view_height = view_height;
+        self.set_scroll(self.scroll_x, self.scroll_y);
+    }
+
+    pub fn scroll_x(&self) -> usize {
+        self.scroll_x
+    }
+
+    pub fn scroll_y(&self) -> usize {
+        self.scroll_y
+    }
+
+    /// max_scroll_x returns the largest horizontal scroll that keeps the view inside the content
+    pub fn max_scroll_x(&self) -> usize {
+        self.width.saturating_sub(self.content_width())
+    }
+
+    /// max_scroll_y returns the largest vertical scroll that keeps the view inside the content
+    pub fn max_scroll_y(&self) -> usize {
+        self.height.saturating_sub(self.content_height())
+    }
+
+    /// set_scroll moves the view within the content, clamped to the content bounds
+    pub fn set_scroll(&mut self, scroll_x: usize, scroll_y: usize) {
+        self.scroll_x = scroll_x.min(self.max_scroll_x());
+        self.scroll_y = scroll_y.min(self.max_scroll_y());
+    }
+
+    /// scroll_by moves the view relative to its current position, clamped to the content bounds
+    pub fn scroll_by(&mut self, dx: isize, dy: isize) {
+        self.set_scroll(
+            self.scroll_x.saturating_add_signed(dx),
+            self.scroll_y.saturating_add_signed(dy),
+        );
+    }
+
+    pub fn set_scrollbar_v(&mut self, scrollbar: &OmmaScrollbar) {
+        self.scrollbar_v = Some(scrollbar.clone());
+    }
+
+    pub fn remove_scrollbar_v(&mut self) {
+        self.scrollbar_v = None;
+    }
+
+    pub fn set_scrollbar_h(&mut self, scrollbar: &OmmaScrollbar) {
+        self.scrollbar_h = Some(scrollbar.clone());
+    }
+
+    pub fn remove_scrollbar_h(&mut self) {
+        self.scrollbar_h = None;
+    }
+
+    /// scrollbar_v_track returns the column, first row and length of the vertical scrollbar
+    fn scrollbar_v_track(&self) -> Option<(usize, usize, usize)> {
+        self.scrollbar_v.as_ref()?;
+        let x = self.view_width.checked_sub(1)?;
+        let (top, bottom, _, right) = self.border_sides();
+        if right {
+            let len = self
+                .view_height
+                .saturating_sub(top as usize + bottom as usize);
+            Some((x, top as usize, len))
+        } else if self.pad.pad_right() > 0 {
+            Some((x, self.pad_top(), self.content_height()))
+        } else {
+            None
+        }
+    }
+
+    /// scrollbar_h_track returns the row, first column and length of the horizontal scrollbar
+    fn scrollbar_h_track(&self) -> Option<(usize, usize, usize)> {
+        self.scrollbar_h.as_ref()?;
+        let y = self.view_height.checked_sub(1)?;
+        let (_, bottom, left, right) = self.border_sides();
+        if bottom {
+            let len = self
+                .view_width
+                .saturating_sub(left as usize + right as usize);
+            Some((y, left as usize, len))
+        } else if self.pad.pad_bottom() > 0 {
+            Some((y, self.pad_left(), self.content_width()))
+        } else {
+            None
+        }
+    }
+
+    /// drag_scrollbar scrolls the window so the thumb of the scrollbar under x, y relative to the
+    /// window is centered there, returns false when x, y is not on a scrollbar
+    pub fn drag_scrollbar(&mut self, x: usize, y: usize) -> bool {
+        if let Some((track_x, start, len)) = self.scrollbar_v_track()
+            && x == track_x
+            && y >= start
+            && y < start + len
+        {
+            let scroll =
+                OmmaScrollbar::scroll_at(len, self.content_height(), self.height, y - start);
+            self.set_scroll(self.scroll_x, scroll);
+            return true;
+        }
+        if let Some((track_y, start, len)) = self.scrollbar_h_track()
+            && y == track_y
+            && x >= start
+            && x < start + len
+        {
+            let scroll = OmmaScrollbar::scroll_at(len, self.content_width(), self.width, x - start);
+            self.set_scroll(scroll, self.scroll_y);
+            return true;
+        }
+        false
+    }
+
     pub fn remove_border(&mut self) {
         self.border = None;
     }
@@ -260,6 +606,10 @@ impl Window {
         self.border = Some(border.clone());
     }
 
+    pub fn has_border(&self) -> bool {
+        self.border.is_some()
+    }
+
     pub fn is_border_hidden(&self) -> bool {
         if let Some(border) = &self.border {
             border.hidden()
@@ -286,17 +636,241 @@ impl Window {
         }
     }
 
+    /// set_border_sides picks which edges of the border are drawn and take up space
+    pub fn set_border_sides(&mut self, top: bool, bottom: bool, left: bool, right: bool) {
+        if let Some(border) = &mut self.border {
+            border.set_sides(top, bottom, left, right)
+        }
+    }
+
+    pub fn title(&self) -> Option<&OmmaLabel> {
+        self.title.as_ref()
+    }
+
+    pub fn set_title(&mut self, title: &OmmaLabel) {
+        self.title = Some(title.clone());
+    }
+
+    pub fn remove_title(&mut self) {
+        self.title = None;
+    }
+
+    pub fn footer(&self) -> Option<&OmmaLabel> {
+        self.footer.as_ref()
+    }
+
+    pub fn set_footer(&mut self, footer: &OmmaLabel) {
+        self.footer = Some(footer.clone());
+    }
+
+    pub fn remove_footer(&mut self) {
+        self.footer = None;
+    }
+
+    /// add_filter appends an enabled filter and returns its index, filters run in the order added
+    pub fn add_filter(&mut self, filter: &OmmaFilter) -> usize {
+        self.filters.push((filter.clone(), true));
+        self.filters.len() - 1
+    }
+
+    /// remove_filter takes the filter at index out of the window, later filters shift down
+    pub fn remove_filter(&mut self, index: usize) -> Result<OmmaFilter, OmmaErr> {
+        self.filter_slot(index)?;
+        Ok(self.filters.remove(index).0)
+    }
+
+    pub fn clear_filters(&mut self) {
+        self.filters.clear();
+    }
+
+    /// filter_mut returns the filter at index for updates such as revealing cells of a mask
+    pub fn filter_mut(&mut self, index: usize) -> Result<&mut OmmaFilter, OmmaErr> {
+        Ok(&mut self.filter_slot(index)?.0)
+    }
+
+    pub fn is_filter_enabled(&self, index: usize) -> bool {
+        self.filters.get(index).is_some_and(|(_, enabled)| *enabled)
+    }
+
+    pub fn toggle_filter(&mut self, index: usize) -> Result<(), OmmaErr> {
+        let slot = self.filter_slot(index)?;
+        slot.1 = !slot.1;
+        Ok(())
+    }
+
+    pub fn set_filter_enabled(&mut self, index: usize, enabled: bool) -> Result<(), OmmaErr> {
+        self.filter_slot(index)?.1 = enabled;
+        Ok(())
+    }
+
+    fn filter_slot(&mut self, index: usize) -> Result<&mut (OmmaFilter, bool), OmmaErr> {
+        let id = self.id;
+        let len = self.filters.len();
+        self.filters.get_mut(index).ok_or_else(|| {
+            OmmaErr::new(&format!(
+                "window_id {} invalid filter {}, window has {} filters",
+                id, index, len
+            ))
+        })
+    }
+
+    /// add_shader appends a shader applied to the window's content cells at blit time
+    pub fn add_shader(&mut self, shader: Rc<dyn OmmaShader>) {
+        self.shaders.push(shader);
+    }
+
+    pub fn clear_shaders(&mut self) {
+        self.shaders.clear();
+    }
+
+    /// shade_span applies a shader to len cells of row y starting at x, the shader sees local_x
+    /// counting from the start of the span
+    pub fn shade_span(
+        &mut self,
+        x: usize,
+        y: usize,
+        len: usize,
+        shader: Rc<dyn OmmaShader>,
+    ) -> Result<(), OmmaErr> {
+        if x + len > self.width || y >= self.height {
+            return Err(OmmaErr::new(&format!(
+                "window_id {} invalid shader span {}:{} len {} (max {}:{})",
+                self.id, x, y, len, self.width, self.height
+            )));
+        }
+        self.spans.push(ShaderSpan { x, y, len, shader });
+        Ok(())
+    }
+
+    pub fn clear_spans(&mut self) {
+        self.spans.clear();
+    }
+
+    /// shade applies span shaders then window shaders to the cell at x, y of the content buffer
+    fn shade(&self, cell: &OmmaCell, x: usize, y: usize, ctx: ShaderCtx) -> OmmaCell {
+        let mut cell = cell.clone();
+        for span in &self.spans {
+            if y == span.y && x >= span.x && x < span.x + span.len {
+                let ctx = ShaderCtx {
+                    local_x: x - span.x,
+                    local_y: 0,
+                    ..ctx
+                };
+                cell = span.shader.shade(&cell, &ctx);
+            }
+        }
+        for shader in &self.shaders {
+            cell = shader.shade(&cell, &ctx);
+        }
+        cell
+    }
+
+    pub fn theme(&self) -> Option<&OmmaTheme> {
+        self.theme.as_deref()
+    }
+
+    /// set_theme replaces the theme of the window and its descendants, they are restyled the next
+    /// time they are drawn
+    pub fn set_theme(&mut self, theme: &OmmaTheme) {
+        self.theme = Some(Rc::new(theme.clone()));
+    }
+
+    pub fn clear_theme(&mut self) {
+        self.theme = None;
+    }
+
+    /// set_top_strip sets cells drawn over the top border, starting right of the corner. Windows
+    /// without a border draw the strip over their top row. EMPTY cells let the border show through.
+    /// Tab containers draw their tabs there instead.
+    pub fn set_top_strip(&mut self, cells: &[OmmaCell]) {
+        self.top_strip = cells.to_vec();
+    }
+
+    pub fn clear_top_strip(&mut self) {
+        self.top_strip.clear();
+    }
+
+    pub fn top_strip(&self) -> &[OmmaCell] {
+        &self.top_strip
+    }
+
+    pub fn shadow(&self) -> Option<&OmmaShadow> {
+        self.shadow.as_ref()
+    }
+
+    pub fn set_shadow(&mut self, shadow: &OmmaShadow) {
+        self.shadow = Some(shadow.clone());
+    }
+
+    pub fn remove_shadow(&mut self) {
+        self.shadow = None;
+    }
+
+    pub fn pixels(&self) -> Option<&OmmaPixels> {
+        self.pixels.as_ref()
+    }
+
+    pub fn pixels_mut(&mut self) -> Option<&mut OmmaPixels> {
+        self.pixels.as_mut()
+    }
+
+    /// set_pixels gives the window an unlit pixel canvas the size of its content buffer. Cells
+    /// with lit pixels are packed into glyphs over the buffer's cells as the window is drawn,
+    /// cells without any show the buffer as it is.
+    pub fn set_pixels(&mut self, mode: PixelMode) {
+        self.pixels = Some(OmmaPixels::new(mode, self.width, self.height));
+    }
+
+    pub fn remove_pixels(&mut self) {
+        self.pixels = None;
+    }
+
+    pub fn tabs(&self) -> Option<&Tabs> {
+        self.tabs.as_ref()
+    }
+
+    pub(crate) fn tabs_mut(&mut self) -> Option<&mut Tabs> {
+        self.tabs.as_mut()
+    }
+
+    /// border_sides returns which of the top, bottom, left and right border edges take up space
+    pub fn border_sides(&self) -> (bool, bool, bool, bool) {
+        match &self.border {
+            Some(border) => (
+                border.side_top(),
+                border.side_bottom(),
+                border.side_left(),
+                border.side_right(),
+            ),
+            None => (false, false, false, false),
+        }
+    }
+
     pub fn pad_top(&self) -> usize {
-        self.pad.pad_top() + if self.border.is_some() { 1 } else { 0 }
+        self.pad.pad_top() + self.border_sides().0 as usize
     }
     pub fn pad_bottom(&self) -> usize {
-        self.pad.pad_bottom() + if self.border.is_some() { 1 } else { 0 }
+        self.pad.pad_bottom() + self.border_sides().1 as usize
     }
     pub fn pad_left(&self) -> usize {
-        self.pad.pad_left() + if self.border.is_some() { 1 } else { 0 }
+        self.pad.pad_left() + self.border_sides().2 as usize
     }
     pub fn pad_right(&self) -> usize {
-        self.pad.pad_right() + if self.border.is_some() { 1 } else { 0 }
+        self.pad.pad_right() + self.border_sides().3 as usize
+    }
+
+    /// content_width returns the number of columns visible inside the border and pad
+    pub fn content_width(&self) -> usize {
+        self.view_width
+            .saturating_sub(self.pad_left().saturating_add(self.pad_right()))
+            .min(self.width)
+    }
+
+    /// content_height returns the number of rows visible inside the border and pad
+    pub fn content_height(&self) -> usize {
+        self.view_height
+            .saturating_sub(self.pad_top().saturating_add(self.pad_bottom()))
+            .min(self.height)
     }
 
     pub fn is_hidden(&self) -> bool {
@@ -339,6 +913,79 @@ impl Window {
         self.objects.push(object_id);
     }
 
+    /// remove_object removes an object id from the window's object list
+    pub(crate) fn remove_object(&mut self, object_id: u32) -> Result<(), OmmaErr> {
+        if let Some(index) = self.objects.iter().position(|x| *x == object_id) {
+            self.objects.remove(index);
+        } else {
+            return Err(OmmaErr::new(&format!(
+                "error removing object {} from window {}, not owned",
+                object_id,
+                self.id(),
+            )));
+        }
+        Ok(())
+    }
+
+    /// release hides the window and frees its contents once it has been detached from the tree
+    pub(crate) fn release(&mut self) {
+        self.hidden = true;
+        self.buffer = Vec::new();
+        self.solid = Vec::new();
+        self.width = 0;
+        self.height = 0;
+        self.children.clear();
+        self.objects.clear();
+        self.spans.clear();
+    }
+
+    /// set_solid marks a cell of the content buffer as blocking object movement. The collision layer
+    /// is only allocated once the first cell is marked.
+    pub fn set_solid(&mut self, x: usize, y: usize, solid: bool) -> Result<(), OmmaErr> {
+        if x >= self.width || y >= self.height {
+            return Err(OmmaErr::new(&format!(
+                "window_id {} invalid collision write to {}:{} (max {}:{})",
+                self.id, x, y, self.width, self.height
+            )));
+        }
+        if self.solid.is_empty() {
+            if !solid {
+                return Ok(());
+            }
+            self.solid = vec![vec![false; self.height]; self.width];
+        }
+        self.solid[x][y] = solid;
+        Ok(())
+    }
+
+    /// is_solid returns true if the cell at x, y of the content buffer blocks object movement
+    pub fn is_solid(&self, x: usize, y: usize) -> bool {
+        self.solid
+            .get(x)
+            .and_then(|col| col.get(y))
+            .is_some_and(|solid| *solid)
+    }
+
+    /// solidify marks every cell of the content buffer drawn with the glyph of cell as solid, such
+    /// as WALL_CELL, returns the number of cells marked
+    pub fn solidify(&mut self, cell: &OmmaCell) -> u32 {
+        let mut marked = 0;
+        for x in 0..self.width {
+            for y in 0..self.height {
+                if self.buffer[x][y].ch == cell.ch {
+                    let _ = self.set_solid(x, y, true);
+                    marked += 1;
+                }
+            }
+        }
+        marked
+    }
+
+    /// clear_solid removes the collision layer
+    pub fn clear_solid(&mut self) {
+        self.solid = Vec::new();
+    }
+
     /// set_ommacell sets a location within the window to a selected ommacell
     pub fn set_ommacell(&mut self, x: usize, y: usize, ommacell: &OmmaCell) -> Result<(), OmmaErr> {
         if x >= self.width || y >= self.height {
@@ -370,42 +1017,120 @@ impl Window {
         Ok(self.buffer[x][y].clone())
     }
 
-    /// blit submits the window's contents into the terminal backplane
+    /// blit submits the window's contents into the terminal backplane. The parent offset is where
+    /// the parent's content origin lands on screen once scrolled. Every cell is clipped to clip,
+    /// the part of the screen the parent's content area covers, and to the screen's top left.
     pub fn blit(
         &self,
         windows: &Vec<Window>,
         objects: &mut Vec<Object>,
         term: &mut OmmaTerm,
-        parent_offset_x: usize,
-        parent_offset_y: usize,
+        clock: &OmmaClock,
+        (parent_offset_x, parent_offset_y): (isize, isize),
+        clip: Option<Clip>,
     ) -> Result<u32, OmmaErr> {
         if self.hidden {
             return Ok(0);
         }
-        let window_offset_x = self.offset_x + parent_offset_x;
-        let window_offset_y = self.offset_y + parent_offset_y;
-        let offset_x = window_offset_x + self.pad_left();
-        let offset_y = window_offset_y + self.pad_top();
-        let c_width = self
-            .view_width
-            .saturating_sub(self.pad_left().saturating_add(self.pad_right()));
-        let c_height = self
-            .view_height
-            .saturating_sub(self.pad_top().saturating_add(self.pad_bottom()));
+        let window_offset_x = self.offset_x as isize + parent_offset_x;
+        let window_offset_y = self.offset_y as isize + parent_offset_y;
+        let offset_x = window_offset_x + self.pad_left() as isize;
+        let offset_y = window_offset_y + self.pad_top() as isize;
+        let c_width = self.content_width();
+        let c_height = self.content_height();
+        let scroll_x = self.scroll_x.min(self.max_scroll_x());
+        let scroll_y = self.scroll_y.min(self.max_scroll_y());
         let mut written = 0;
+        let put =
+            |term: &mut OmmaTerm, x: isize, y: isize, cell: &OmmaCell| match clip_point(clip, x, y)
+            {
+                Some((x, y)) => term.put_cell_at(x, y, cell),
+                None => Ok(0),
+            };
+
+        // Filters see the content buffer's coordinates, wherever the view is scrolled to
+        let filters: Vec<OmmaFilter> = self
+            .filters
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(filter, _)| filter.clone())
+            .collect();
+        let filtered = !filters.is_empty();
+        if filtered {
+            term.push_filters(
+                filters,
+                offset_x - scroll_x as isize,
+                offset_y - scroll_y as isize,
+            );
+        }
+        if let Some(theme) = &self.theme {
+            term.push_theme(theme.clone());
+        }
+
         // Skip drawing virtual window contents
         if !self.virt {
             // Draw raw window contents
+            let shaded = !self.shaders.is_empty() || !self.spans.is_empty();
             for x in 0..c_width {
                 for y in 0..c_height {
-                    written += term.put_cell_at(x + offset_x, y + offset_y, &self.buffer[x][y])?;
+                    let Some((screen_x, screen_y)) =
+                        clip_point(clip, offset_x + x as isize, offset_y + y as isize)
+                    else {
+                        continue;
+                    };
+                    let (buffer_x, buffer_y) = (x + scroll_x, y + scroll_y);
+                    let cell = &self.buffer[buffer_x][buffer_y];
+                    let packed = self
+                        .pixels
+                        .as_ref()
+                        .filter(|pixels| pixels.is_lit(buffer_x, buffer_y))
+                        .map(|pixels| pixels.cell(buffer_x, buffer_y, cell));
+                    let cell = packed.as_ref().unwrap_or(cell);
+                    let cut = x + 1 == c_width
+                        || clip_point(clip, screen_x as isize + 1, screen_y as isize).is_none();
+                    if cut && cell.is_wide() {
+                        // The right half of a wide character would fall outside the view
+                        let blank = OmmaCell {
+                            ch: ' ',
+                            tail: None,
+                            ..cell.clone()
+                        };
+                        written += term.put_cell_at(screen_x, screen_y, &blank)?;
+                    } else if shaded && cell.ch != EMPTY {
+                        let ctx = ShaderCtx {
+                            screen_x,
+                            screen_y,
+                            local_x: buffer_x,
+                            local_y: buffer_y,
+                            frame: clock.frame(),
+                            elapsed: clock.elapsed(),
+                            window_id: self.id,
+                        };
+                        let cell = self.shade(cell, buffer_x, buffer_y, ctx);
+                        written += term.put_cell_at(screen_x, screen_y, &cell)?;
+                    } else {
+                        written += term.put_cell_at(screen_x, screen_y, cell)?;
+                    }
                 }
             }
 
-            // Search the list of child objects and draw them
-            for &object_id in &self.objects {
-                let object = Session::object_direct(objects, object_id)?;
-                object.blit(term, offset_x, offset_y)?;
+            // Draw child objects, lower layers first, clipped to the part of the viewport left
+            // inside the clip
+            let (left, top, right, bottom) = clip_rect(clip, offset_x, offset_y, c_width, c_height);
+            let (left, top) = (left.max(0), top.max(0));
+            if right > left && bottom > top {
+                let view = ObjectView {
+                    origin_x: left as usize,
+                    origin_y: top as usize,
+                    scroll_x: scroll_x + (left - offset_x) as usize,
+                    scroll_y: scroll_y + (top - offset_y) as usize,
+                    width: (right - left) as usize,
+                    height: (bottom - top) as usize,
+                };
+                for object_id in Session::layered(objects, &self.objects) {
+                    let object = Session::object_direct(objects, object_id)?;
+                    object.blit(term, &view, clock)?;
+                }
             }
 
             // Blank pad
@@ -419,60 +1144,127 @@ impl Window {
                     let in_content_y = y >= inner_y_start && y < inner_y_end;
                     if !(in_content_x && in_content_y) {
                         // Borders are transparent instead of blank if not rendered
-                        written += if self.border.is_some()
-                            && (x == 0
-                                || y == 0
-                                || x == self.view_width - 1
-                                || y == self.view_height - 1)
-                        {
-                            term.put_cell_at(x + window_offset_x, y + window_offset_y, &EMPTY_CELL)?
-                        } else {
-                            term.put_cell_at(x + window_offset_x, y + window_offset_y, &BLANK_CELL)?
-                        }
+                        let on_border = self.border.as_ref().is_some_and(|border| {
+                            border
+                                .cell_at(x, y, self.view_width, self.view_height)
+                                .is_some()
+                        });
+                        let cell = if on_border { &EMPTY_CELL } else { &BLANK_CELL };
+                        let (screen_x, screen_y) =
+                            (x as isize + window_offset_x, y as isize + window_offset_y);
+                        written += put(term, screen_x, screen_y, cell)?;
                     }
                 }
             }
 
             // Draw border
-            // TODO: stash this in border code so we can just do a border.blit
             if let Some(border) = &self.border
                 && !border.hidden()
             {
                 for x in 0..self.view_width {
-                    term.put_cell_at(x + window_offset_x, window_offset_y, border.border_top())?;
-                    term.put_cell_at(
-                        x + window_offset_x,
-                        self.view_height + window_offset_y - 1,
-                        border.border_bottom(),
-                    )?;
+                    for y in 0..self.view_height {
+                        let Some(cell) = border.cell_at(x, y, self.view_width, self.view_height)
+                        else {
+                            continue;
+                        };
+                        let Some((screen_x, screen_y)) = clip_point(
+                            clip,
+                            x as isize + window_offset_x,
+                            y as isize + window_offset_y,
+                        ) else {
+                            continue;
+                        };
+                        // Borders are drawn in the border role unless they name a role of their own
+                        let cell = OmmaCell {
+                            role: cell.role.or(Some(OmmaRole::Border)),
+                            ..cell.clone()
+                        };
+                        // Borders join into junctions with borders already drawn this frame
+                        if border.unmerged() {
+                            term.put_cell_at(screen_x, screen_y, &cell)?;
+                        } else {
+                            term.put_border_at(screen_x, screen_y, &cell)?;
+                        }
+                    }
                 }
-                for y in 0..self.view_height {
-                    term.put_cell_at(window_offset_x, y + window_offset_y, border.border_left())?;
-                    term.put_cell_at(
-                        self.view_width + window_offset_x - 1,
-                        y + window_offset_y,
-                        border.border_right(),
-                    )?;
+            }
+
+            // Draw scrollbars over the border or pad
+            if let Some((x, start, len)) = self.scrollbar_v_track()
+                && let Some(scrollbar) = &self.scrollbar_v
+                && let Some((thumb, thumb_len)) =
+                    OmmaScrollbar::thumb_span(len, c_height, self.height, scroll_y)
+            {
+                for y in 0..len {
+                    let cell = if y >= thumb && y < thumb + thumb_len {
+                        scrollbar.thumb()
+                    } else {
+                        scrollbar.track()
+                    };
+                    let (screen_x, screen_y) = (
+                        x as isize + window_offset_x,
+                        (y + start) as isize + window_offset_y,
+                    );
+                    written += put(term, screen_x, screen_y, cell)?;
+                }
+            }
+            if let Some((y, start, len)) = self.scrollbar_h_track()
+                && let Some(scrollbar) = &self.scrollbar_h
+                && let Some((thumb, thumb_len)) =
+                    OmmaScrollbar::thumb_span(len, c_width, self.width, scroll_x)
+            {
+                for x in 0..len {
+                    let cell = if x >= thumb && x < thumb + thumb_len {
+                        scrollbar.thumb()
+                    } else {
+                        scrollbar.track()
+                    };
+                    let (screen_x, screen_y) = (
+                        (x + start) as isize + window_offset_x,
+                        y as isize + window_offset_y,
+                    );
+                    written += put(term, screen_x, screen_y, cell)?;
                 }
+            }
 
-                term.put_cell_at(window_offset_x, window_offset_y, border.border_corner_tl())?;
-                term.put_cell_at(
-                    window_offset_x,
-                    self.view_height + window_offset_y - 1,
-                    border.border_corner_bl(),
-                )?;
-                term.put_cell_at(
-                    self.view_width + window_offset_x - 1,
-                    window_offset_y,
-                    border.border_corner_tr(),
-                )?;
-                term.put_cell_at(
-                    self.view_width + window_offset_x - 1,
-                    self.view_height + window_offset_y - 1,
-                    border.border_corner_br(),
-                )?;
+            // Draw title, footer and top strip, kept clear of the corners when bordered
+            let (_, _, left, right) = self.border_sides();
+            let strip_start = left as usize;
+            let strip_end = self.view_width.saturating_sub(right as usize);
+            let strip_len = strip_end.saturating_sub(strip_start);
+            if self.view_height > 0 {
+                let bottom = (self.view_height - 1) as isize + window_offset_y;
+                let rows = [
+                    (
+                        window_offset_y,
+                        self.title.as_ref().map(|l| l.cells(strip_len)),
+                    ),
+                    (bottom, self.footer.as_ref().map(|l| l.cells(strip_len))),
+                    (
+                        window_offset_y,
+                        Some(
+                            self.tabs
+                                .as_ref()
+                                .map_or(self.top_strip.clone(), Tabs::strip),
+                        ),
+                    ),
+                ];
+                for (y, strip) in rows {
+                    for (x, cell) in (strip_start..strip_end).zip(strip.iter().flatten()) {
+                        written += put(term, x as isize + window_offset_x, y, cell)?;
+                    }
+                }
             }
         }
+
+        // Each child is drawn over its shadow, clipped to this window's content area unless the
+        // window is virtual and has none. Children scroll along with the content.
+        let child_clip = if self.virt {
+            clip
+        } else {
+            Some(clip_rect(clip, offset_x, offset_y, c_width, c_height))
+        };
+        let origin = (offset_x - scroll_x as isize, offset_y - scroll_y as isize);
         for window_id in &self.children {
             if &self.id() == window_id {
                 if window_id == &0 {
@@ -484,12 +1276,113 @@ impl Window {
                     )));
                 }
             }
-            written +=
-                windows[*window_id as usize].blit(windows, objects, term, offset_x, offset_y)?;
+            let child = &windows[*window_id as usize];
+            written += child.blit_shadow(term, origin, child_clip)?;
+            written += child.blit(windows, objects, term, clock, origin, child_clip)?;
+        }
+        if self.theme.is_some() {
+            term.pop_theme();
+        }
+        if filtered {
+            term.pop_filters();
+        }
+        Ok(written)
+    }
+
+    /// blit_shadow draws the window's shadow relative to its parent's content origin on screen once
+    /// scrolled, clipped like blit
+    fn blit_shadow(
+        &self,
+        term: &mut OmmaTerm,
+        (parent_offset_x, parent_offset_y): (isize, isize),
+        clip: Option<Clip>,
+    ) -> Result<u32, OmmaErr> {
+        let Some(shadow) = &self.shadow else {
+            return Ok(0);
+        };
+        if self.hidden {
+            return Ok(0);
+        }
+        let cell = shadow.cell();
+        let (view_width, view_height) = (self.view_width as isize, self.view_height as isize);
+        let mut written = 0;
+        for x in 0..view_width {
+            for y in 0..view_height {
+                let (shadow_x, shadow_y) = (x + shadow.offset_x(), y + shadow.offset_y());
+                // The window itself covers the part of the shadow beneath it
+                if (0..view_width).contains(&shadow_x) && (0..view_height).contains(&shadow_y) {
+                    continue;
+                }
+                let local_x = shadow_x + self.offset_x as isize;
+                let local_y = shadow_y + self.offset_y as isize;
+                if local_x < 0 || local_y < 0 {
+                    continue;
+                }
+                let Some((screen_x, screen_y)) =
+                    clip_point(clip, local_x + parent_offset_x, local_y + parent_offset_y)
+                else {
+                    continue;
+                };
+                if term.cell_at(screen_x, screen_y).is_some() {
+                    written += term.put_cell_at(screen_x, screen_y, &cell)?;
+                }
+            }
         }
         Ok(written)
     }
 
+    /// tab_at returns the tab container and tab drawn at screen position x, y, searching this
+    /// window and its descendants with the parent offset and clip blit uses. Windows drawn later
+    /// win.
+    pub(crate) fn tab_at(
+        &self,
+        windows: &[Window],
+        parent_offset_x: isize,
+        parent_offset_y: isize,
+        clip: Option<Clip>,
+        x: usize,
+        y: usize,
+    ) -> Option<(u32, usize)> {
+        if self.hidden {
+            return None;
+        }
+        let window_x = self.offset_x as isize + parent_offset_x;
+        let window_y = self.offset_y as isize + parent_offset_y;
+        let (_, _, left, right) = self.border_sides();
+        let strip_x = x as isize - window_x - left as isize;
+        let strip_len = self
+            .view_width
+            .saturating_sub(left as usize + right as usize);
+        let mut hit = self
+            .tabs
+            .as_ref()
+            .filter(|_| !self.virt && y as isize == window_y)
+            .filter(|_| (0..strip_len as isize).contains(&strip_x))
+            .filter(|_| clip_point(clip, x as isize, y as isize).is_some())
+            .and_then(|tabs| tabs.tab_at(strip_x as usize))
+            .map(|tab| (self.id, tab));
+        let offset_x = window_x + self.pad_left() as isize;
+        let offset_y = window_y + self.pad_top() as isize;
+        let child_clip = if self.virt {
+            clip
+        } else {
+            let (width, height) = (self.content_width(), self.content_height());
+            Some(clip_rect(clip, offset_x, offset_y, width, height))
+        };
+        let origin_x = offset_x - self.scroll_x.min(self.max_scroll_x()) as isize;
+        let origin_y = offset_y - self.scroll_y.min(self.max_scroll_y()) as isize;
+        for window_id in &self.children {
+            if *window_id == self.id {
+                continue;
+            }
+            let child = &windows[*window_id as usize];
+            if let Some(child_hit) = child.tab_at(windows, origin_x, origin_y, child_clip, x, y) {
+                hit = Some(child_hit);
+            }
+        }
+        hit
+    }
+
     /// fill fills the window with a single ommacell
     pub fn fill(&mut self, cell: &OmmaCell) -> Result<u32, OmmaErr> {
         for x in 0..self.width {
@@ -501,37 +1394,236 @@ impl Window {
         Ok(self.view_width as u32 * self.view_height as u32)
     }
 
-    /// string_raw prints a string into a window directly, using the properties from ommacell
-    pub fn string_raw(
+    /// string_shaded prints a string like string_raw and applies a shader to the cells it covers
+    pub fn string_shaded(
         &mut self,
         x: usize,
         y: usize,
         cell: &OmmaCell,
-        string: String,
+        string: impl Into<StyledText>,
+        shader: Rc<dyn OmmaShader>,
     ) -> Result<u32, OmmaErr> {
-        let mut x = x;
-        let max_width = self.width - 1;
-        let max_height = self.height - 1;
+        let written = self.string_raw(x, y, cell, string)?;
+        self.shade_span(x, y, written as usize, shader)?;
+        Ok(written)
+    }
 
-        if x + string.len() > max_width || y > max_height {
+    /// string_raw prints a string into a window directly, using the properties from ommacell.
+    /// StyledText spans are drawn over ommacell in their own style. Wide characters take two
+    /// columns and combining marks stay with the character before them, returns the number of
+    /// columns written.
+    pub fn string_raw(
+        &mut self,
+        x: usize,
+        y: usize,
+        cell: &OmmaCell,
+        string: impl Into<StyledText>,
+    ) -> Result<u32, OmmaErr> {
+        let string = string.into();
+        let cells = string.cells(cell);
+        if x + cells.len() > self.width || y >= self.height {
             return Err(OmmaErr::new(&format!(
                 "window_id {} invalid write_window_string {}:{} (max {}:{}) = {}",
                 self.id(),
                 x,
                 y,
-                max_width,
-                max_height,
+                self.width.saturating_sub(1),
+                self.height.saturating_sub(1),
                 string
             )));
         }
 
-        let mut cell = cell.clone();
-        for ch in string.chars() {
-            cell.ch = ch;
-            self.set_ommacell(x, y, &cell)?;
-            x += 1;
+        for (x, cell) in (x..).zip(&cells) {
+            self.set_ommacell(x, y, cell)?;
         }
 
-        Ok(string.len() as u32)
+        Ok(cells.len() as u32)
+    }
+
+    /// markup parses inline markup such as "[red]Yes![/]" into StyledText and prints it like
+    /// string_raw
+    pub fn markup(
+        &mut self,
+        x: usize,
+        y: usize,
+        cell: &OmmaCell,
+        markup: &str,
+    ) -> Result<u32, OmmaErr> {
+        self.string_raw(x, y, cell, StyledText::parse(markup)?)
+    }
+
+    /// plot sets the cell at x, y of the content buffer if it lies inside, returns the number of
+    /// cells written. The drawing methods below clip to the content buffer the same way.
+    pub fn plot(&mut self, x: isize, y: isize, cell: &OmmaCell) -> u32 {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return 0;
+        }
+        self.buffer[x as usize][y as usize] = cell.clone();
+        1
+    }
+
+    /// line draws a line from x0, y0 to x1, y1 inclusive
+    pub fn line(&mut self, x0: isize, y0: isize, x1: isize, y1: isize, cell: &OmmaCell) -> u32 {
+        line_points(x0, y0, x1, y1, self.width, self.height)
+            .into_iter()
+            .map(|(x, y)| self.plot(x, y, cell))
+            .sum()
+    }
+
+    /// rect draws the outline of a width by height rectangle with its top left corner at x, y
+    pub fn rect(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        cell: &OmmaCell,
+    ) -> u32 {
+        if width == 0 || height == 0 {
+            return 0;
+        }
+        let (right, bottom) = (x + width as isize - 1, y + height as isize - 1);
+        let mut written = self.line(x, y, right, y, cell);
+        if bottom > y {
+            written += self.line(x, bottom, right, bottom, cell);
+        }
+        if bottom > y + 1 {
+            written += self.line(x, y + 1, x, bottom - 1, cell);
+            if right > x {
+                written += self.line(right, y + 1, right, bottom - 1, cell);
+            }
+        }
+        written
+    }
+
+    /// rect_filled fills a width by height rectangle with its top left corner at x, y
+    pub fn rect_filled(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        cell: &OmmaCell,
+    ) -> u32 {
+        let mut written = 0;
+        for x in x..x + width as isize {
+            for y in y..y + height as isize {
+                written += self.plot(x, y, cell);
+            }
+        }
+        written
+    }
+
+    /// circle draws the outline of a circle. Cells are about twice as tall as they are wide, use
+    /// ellipse with rx twice ry for circles that look round.
+    pub fn circle(&mut self, cx: isize, cy: isize, r: usize, cell: &OmmaCell) -> u32 {
+        self.ellipse(cx, cy, r, r, cell)
+    }
+
+    pub fn circle_filled(&mut self, cx: isize, cy: isize, r: usize, cell: &OmmaCell) -> u32 {
+        self.ellipse_filled(cx, cy, r, r, cell)
+    }
+
+    /// ellipse draws the outline of an ellipse centered on cx, cy with radii rx and ry
+    pub fn ellipse(&mut self, cx: isize, cy: isize, rx: usize, ry: usize, cell: &OmmaCell) -> u32 {
+        let mut written = 0;
+        for (dy, inner, outer) in ellipse_rows(rx, ry) {
+            for dx in inner..=outer {
+                written += self.plot(cx + dx, cy + dy, cell);
+                if dx != 0 {
+                    written += self.plot(cx - dx, cy + dy, cell);
+                }
+            }
+        }
+        written
+    }
+
+    pub fn ellipse_filled(
+        &mut self,
+        cx: isize,
+        cy: isize,
+        rx: usize,
+        ry: usize,
+        cell: &OmmaCell,
+    ) -> u32 {
+        let mut written = 0;
+        for (dy, _, outer) in ellipse_rows(rx, ry) {
+            for dx in -outer..=outer {
+                written += self.plot(cx + dx, cy + dy, cell);
+            }
+        }
+        written
+    }
+
+    /// flood_fill replaces the cells matching the one at x, y that connect to it horizontally or
+    /// vertically, returns the number of cells replaced
+    pub fn flood_fill(&mut self, x: usize, y: usize, cell: &OmmaCell) -> u32 {
+        let Some(target) = self.buffer.get(x).and_then(|col| col.get(y)).cloned() else {
+            return 0;
+        };
+        if &target == cell {
+            return 0;
+        }
+        let mut written = 0;
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            if self.buffer[x][y] != target {
+                continue;
+            }
+            self.buffer[x][y] = cell.clone();
+            written += 1;
+            if x > 0 {
+                stack.push((x - 1, y));
+            }
+            if x + 1 < self.width {
+                stack.push((x + 1, y));
+            }
+            if y > 0 {
+                stack.push((x, y - 1));
+            }
+            if y + 1 < self.height {
+                stack.push((x, y + 1));
+            }
+        }
+        written
+    }
+
+    /// copy_region copies the width by height cells with their top left corner at x, y, cut
+    /// short where they run past the content buffer
+    pub fn copy_region(&self, x: usize, y: usize, width: usize, height: usize) -> OmmaRegion {
+        let x_end = (x + width).min(self.width);
+        let y_end = (y + height).min(self.height);
+        let cells = self
+            .buffer
+            .get(x..x_end)
+            .unwrap_or_default()
+            .iter()
+            .map(|col| col[y.min(y_end)..y_end].to_vec())
+            .collect();
+        OmmaRegion::from_cells(cells)
+    }
+
+    /// paste_region draws region with its top left corner at x, y. Cells equal to key are
+    /// skipped and leave the cells beneath them alone.
+    pub fn paste_region(
+        &mut self,
+        x: isize,
+        y: isize,
+        region: &OmmaRegion,
+        key: Option<&OmmaCell>,
+    ) -> u32 {
+        let mut written = 0;
+        for region_x in 0..region.width() {
+            for region_y in 0..region.height() {
+                let Some(cell) = region.get(region_x, region_y) else {
+                    continue;
+                };
+                if key == Some(cell) {
+                    continue;
+                }
+                written += self.plot(x + region_x as isize, y + region_y as isize, cell);
+            }
+        }
+        written
     }
 }
@@ -0,0 +1,96 @@
+use ommatidium::cell::{FLOOR_CELL, OVERLAY_CELL, OmmaCell};
+use ommatidium::color::{BLACK, BLUE, DARK_GREY, OmmaColor, RED, WHITE};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// tile is a dark floor tile on a black background
+    fn tile() -> OmmaCell {
+        OmmaCell {
+            fg: Some(DARK_GREY),
+            bg: Some(BLACK),
+            ..FLOOR_CELL
+        }
+    }
+
+    #[test]
+    fn inherit_keeps_the_glyph() {
+        let tint = OmmaCell {
+            bg: Some(BLUE.with_alpha(128)),
+            ..OVERLAY_CELL
+        };
+        let tinted = tint.over(&tile());
+        assert!(tinted.ch == '.' && tinted.fg == Some(DARK_GREY));
+        assert!(tinted.bg == Some(OmmaColor::new(0, 0, 128)));
+    }
+
+    #[test]
+    fn glyph_keeps_the_background() {
+        let cursor = OmmaCell {
+            ch: 'X',
+            fg: Some(WHITE),
+            ..OVERLAY_CELL
+        };
+        let marked = cursor.over(&tile());
+        assert!(marked.ch == 'X' && marked.fg == Some(WHITE) && marked.bg == Some(BLACK));
+    }
+
+    #[test]
+    fn translucent_bg_over_a_terminal_default_bg() {
+        // The terminal's default color is unknown, so there is nothing to blend with
+        let bare = OmmaCell { bg: None, ..tile() };
+        let strong = OmmaCell {
+            bg: Some(RED.with_alpha(192)),
+            ..OVERLAY_CELL
+        };
+        let faint = OmmaCell {
+            bg: Some(RED.with_alpha(64)),
+            ..OVERLAY_CELL
+        };
+        assert!(strong.over(&bare).bg == Some(RED));
+        assert!(faint.over(&bare).bg.is_none());
+    }
+
+    #[test]
+    fn overlay_windows() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(5, 10)?;
+        let map = session
+            .new_window(6, 3)
+            .fill(&tile())
+            .submit(&mut session)?;
+
+        // A selection highlight tints a row of tiles and a cursor sits on top of it
+        let highlight = OmmaCell {
+            bg: Some(BLUE.with_alpha(128)),
+            ..OVERLAY_CELL
+        };
+        session
+            .new_window(3, 1)
+            .parent(map)
+            .offset(1, 1)
+            .fill(&highlight)
+            .submit(&mut session)?;
+        let cursor = OmmaCell {
+            ch: 'X',
+            fg: Some(WHITE),
+            ..OVERLAY_CELL
+        };
+        session
+            .new_window(1, 1)
+            .parent(map)
+            .offset(2, 1)
+            .fill(&cursor)
+            .submit(&mut session)?;
+
+        session.compose()?;
+        let navy = Some(OmmaColor::new(0, 0, 128));
+        let cell = |x| session.cell_at(x, 1).cloned().unwrap_or_default();
+        assert!(cell(1).ch == '.' && cell(1).bg == navy);
+        assert!(cell(2).ch == 'X' && cell(2).bg == navy);
+        assert!(cell(4).bg == Some(BLACK));
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use ommatidium::banner::{Banner, FontLayout, OmmaFont};
+use ommatidium::cell::{FLOOR_CELL, OmmaCell};
+use ommatidium::color::{BLUE, GREEN, RED};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+use ommatidium::theme::OmmaRole;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_font() {
+        // Each letter is five rows tall with a blank column after it
+        let died = Banner::new("YOU DIED");
+        assert!(OmmaFont::default_font().layout() == FontLayout::FullWidth);
+        assert!(died.width() == 33 && died.height() == 5);
+        assert!(died.rows()[0].starts_with("█ █  ██  █ █ "));
+        assert!(Banner::new("ok").rows() == Banner::new("OK").rows());
+    }
+
+    #[test]
+    fn glyph_colors_cycle_per_letter() {
+        let colored = Banner::new("HI!").glyph_colors(&[RED, GREEN]).region();
+        assert!(colored.get(0, 0).and_then(|cell| cell.fg) == Some(RED));
+        assert!(colored.get(4, 0).and_then(|cell| cell.fg) == Some(GREEN));
+        assert!(colored.get(8, 0).and_then(|cell| cell.fg) == Some(RED));
+        assert!(colored.get(0, 0).is_some_and(|cell| cell.role.is_none()));
+    }
+
+    #[test]
+    fn gradients_run_across_the_banner() {
+        let faded = Banner::new("HI").gradient(&[RED, BLUE]).region();
+        assert!(faded.get(0, 0).and_then(|cell| cell.fg) == Some(RED));
+        assert!(faded.get(faded.width() - 2, 0).and_then(|cell| cell.fg) != Some(RED));
+    }
+
+    #[test]
+    fn lines_stack() {
+        let stacked = Banner::new("A\nB").cell(&OmmaCell::new(' ', Some(RED), None, 0));
+        assert!(stacked.height() == 10 && stacked.rows().len() == 10);
+    }
+
+    #[test]
+    fn missing_font_file() {
+        assert!(OmmaFont::load("no/such/font.flf").is_err());
+    }
+
+    #[test]
+    fn drawn_into_windows() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 40)?;
+        let screen = session
+            .new_window(40, 6)
+            .fill(&FLOOR_CELL)
+            .submit(&mut session)?;
+
+        // Spaces leave the window's cells alone, letters take the title role
+        let died = Banner::new("YOU DIED");
+        let written = died.draw(session.window(screen)?, 1, 1)?;
+        let window = session.window(screen)?;
+        assert!(written > 0 && written < 33 * 5);
+        let corner = window.get_ommacell(1, 1)?;
+        assert!(corner.ch == '█' && corner.role == Some(OmmaRole::Title));
+        assert!(window.get_ommacell(2, 1)?.ch == '.');
+
+        // Text wider than the window is clipped, drawing past its edge fails
+        assert!(Banner::new("WIDER THAN THIS WINDOW").draw(session.window(screen)?, 0, 0)? > 0);
+        assert!(died.draw(session.window(screen)?, 40, 0).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use ommatidium::border::BOX_SINGLE_BORDER;
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn border_flags_before_border() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 10)?;
+        session
+            .new_window(4, 3)
+            .border(&BOX_SINGLE_BORDER)
+            .submit(&mut session)?;
+        let window_id = session
+            .new_window(4, 3)
+            .offset(3, 0)
+            .border_sides(true, true, true, false)
+            .border_unmerged()
+            .border(&BOX_SINGLE_BORDER)
+            .submit(&mut session)?;
+        let window = session.window(window_id)?;
+        assert!(window.border_sides() == (true, true, true, false));
+        assert!(window.pad_right() == 0);
+
+        // Unmerged, the corner is drawn over the first window's corner instead of joining it
+        session.compose()?;
+        assert!(session.cell_at(3, 0).map(|cell| cell.ch) == Some('┌'));
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use ommatidium::cell::{FLOOR_CELL, OmmaCell, WALL_CELL};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+use ommatidium::window::Window;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ch returns the glyph at x, y of a window's content or a space outside it
+    fn ch(window: &Window, x: usize, y: usize) -> char {
+        window.get_ommacell(x, y).map(|cell| cell.ch).unwrap_or(' ')
+    }
+
+    #[test]
+    fn canvas() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 20)?;
+        let map = session
+            .new_window(12, 8)
+            .fill(&FLOOR_CELL)
+            .submit(&mut session)?;
+        let window = session.window(map)?;
+
+        // Shapes clip to the buffer instead of failing
+        assert!(window.line(-2, 0, 3, 0, &WALL_CELL) == 4);
+        assert!(window.line(0, 0, 3, 3, &WALL_CELL) == 4 && ch(window, 2, 2) == '#');
+        assert!(window.plot(12, 0, &WALL_CELL) == 0);
+        assert!(window.rect_filled(10, 6, 4, 4, &WALL_CELL) == 4);
+
+        // A closed room: outline, then flood the floor inside it
+        window.fill(&FLOOR_CELL)?;
+        assert!(window.rect(1, 1, 5, 4, &WALL_CELL) == 14);
+        assert!(ch(window, 5, 4) == '#' && ch(window, 3, 2) == '.');
+        let water = OmmaCell {
+            ch: '~',
+            ..FLOOR_CELL
+        };
+        assert!(window.flood_fill(3, 2, &water) == 6);
+        assert!(ch(window, 0, 0) == '.' && ch(window, 4, 3) == '~');
+
+        // Circles and ellipses are symmetric about their center
+        window.fill(&FLOOR_CELL)?;
+        window.ellipse(6, 4, 4, 2, &WALL_CELL);
+        assert!(ch(window, 2, 4) == '#' && ch(window, 10, 4) == '#');
+        assert!(ch(window, 6, 2) == '#' && ch(window, 6, 6) == '#');
+        assert!(ch(window, 6, 4) == '.');
+        assert!(window.circle_filled(6, 4, 1, &water) == 5 && ch(window, 6, 4) == '~');
+        Ok(())
+    }
+}
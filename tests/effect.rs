@@ -0,0 +1,58 @@
+use ommatidium::cell::DEFAULT_CELL;
+use ommatidium::effect::EffectTarget;
+use ommatidium::error::OmmaErr;
+use ommatidium::particle::ParticleEmitter;
+use ommatidium::session::Session;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temporary_windows() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 10)?;
+
+        // A popup that expires on the third frame at 60 frames per second
+        let popup = session
+            .new_window(4, 1)
+            .offset(1, 1)
+            .fill(&DEFAULT_CELL)
+            .submit(&mut session)?;
+        session
+            .window(popup)?
+            .string_raw(0, 0, &DEFAULT_CELL, "Hi!".to_string())?;
+        session
+            .new_effect(EffectTarget::Window(popup))
+            .lifetime(Duration::from_millis(40))
+            .submit(&mut session)?;
+
+        // A floating damage number that drifts up a cell every five frames
+        let mut emitter = ParticleEmitter::new(0.0, 4.0).lifetime(Duration::from_millis(200));
+        emitter.emit_text("12", 0.0, -12.0);
+        let canvas = session.new_window(5, 5).offset(5, 0);
+        let float = emitter.submit(&mut session, canvas)?;
+        assert!(session.effect_count() == 2);
+
+        session.compose()?;
+        assert!(session.cell_at(1, 1).map(|cell| cell.ch) == Some('H'));
+        assert!(session.cell_at(6, 4).map(|cell| cell.ch) == Some('2'));
+
+        for _ in 0..3 {
+            session.tick()?;
+        }
+        session.compose()?;
+        assert!(session.effect_count() == 1);
+        assert!(!session.window(0)?.children().contains(&popup));
+        assert!(session.window(0)?.children().contains(&float));
+        assert!(session.cell_at(1, 1).map(|cell| cell.ch) != Some('H'));
+        assert!(session.cell_at(5, 3).map(|cell| cell.ch) == Some('1'));
+
+        for _ in 0..10 {
+            session.tick()?;
+        }
+        assert!(session.effect_count() == 0);
+        assert!(!session.window(0)?.children().contains(&float));
+        Ok(())
+    }
+}
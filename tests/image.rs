@@ -0,0 +1,127 @@
+use ommatidium::cell::DEFAULT_CELL;
+use ommatidium::color::{BLACK, BLUE, OmmaColor, RED, WHITE};
+use ommatidium::error::OmmaErr;
+use ommatidium::image::{Dither, OmmaImage, ansi_palette, grey_palette};
+use ommatidium::session::Session;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// bmp encodes a bottom up BMP with a palette when bits is 8, or BGR pixels when bits is 24
+    fn bmp(
+        width: usize,
+        height: usize,
+        bits: usize,
+        palette: &[OmmaColor],
+        rows: &[&[u8]],
+    ) -> Vec<u8> {
+        let stride = (bits * width).div_ceil(32) * 4;
+        let data = 54 + palette.len() * 4;
+        let mut bytes = b"BM".to_vec();
+        for field in [(data + stride * height) as u32, 0, data as u32, 40] {
+            bytes.extend(field.to_le_bytes());
+        }
+        bytes.extend((width as u32).to_le_bytes());
+        bytes.extend((height as u32).to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend((bits as u16).to_le_bytes());
+        for field in [0u32, 0, 0, 0, palette.len() as u32, 0] {
+            bytes.extend(field.to_le_bytes());
+        }
+        for color in palette {
+            bytes.extend([color.b, color.g, color.r, 0]);
+        }
+        for row in rows.iter().rev() {
+            let mut row = row.to_vec();
+            row.resize(stride, 0);
+            bytes.extend(row);
+        }
+        bytes
+    }
+
+    /// palettized is a 3x3 BMP with a palette of black, red and blue
+    fn palettized() -> Result<OmmaImage, OmmaErr> {
+        OmmaImage::parse(&bmp(
+            3,
+            3,
+            8,
+            &[BLACK, RED, BLUE],
+            &[&[1, 1, 0], &[2, 1, 0], &[0, 2, 2]],
+        ))
+    }
+
+    #[test]
+    fn palettized_bmp() -> Result<(), OmmaErr> {
+        let image = palettized()?;
+        assert!(image.width() == 3 && image.height() == 3);
+        assert!(image.get(0, 0) == Some(RED) && image.get(0, 1) == Some(BLUE));
+        Ok(())
+    }
+
+    #[test]
+    fn truecolor_bmp_stores_bgr() -> Result<(), OmmaErr> {
+        let image = OmmaImage::parse(&bmp(1, 1, 24, &[], &[&[30, 240, 250]]))?;
+        assert!(image.get(0, 0) == Some(OmmaColor::new(250, 240, 30)));
+        Ok(())
+    }
+
+    #[test]
+    fn quantize_picks_the_nearest_color() {
+        let image = OmmaImage::new(1, 1, &OmmaColor::new(250, 240, 30));
+        let ansi = image.quantize(&ansi_palette(), Dither::None);
+        assert!(ansi.get(0, 0) == Some(OmmaColor::new(255, 255, 0)));
+    }
+
+    #[test]
+    fn dithering_averages_out() {
+        // Mid grey in black and white is all white undithered and about half white dithered
+        let grey = OmmaImage::new(8, 8, &OmmaColor::new(128, 128, 128));
+        let whites = |image: &OmmaImage| {
+            (0..64)
+                .filter(|i| image.get(i % 8, i / 8) == Some(WHITE))
+                .count()
+        };
+        assert!(whites(&grey.quantize(&grey_palette(2), Dither::None)) == 64);
+        let dithered = grey.quantize(&grey_palette(2), Dither::FloydSteinberg);
+        assert!((28..=36).contains(&whites(&dithered)));
+    }
+
+    #[test]
+    fn unsupported_images() {
+        assert!(OmmaImage::parse(&bmp(1, 1, 16, &[], &[&[0, 0]])).is_err());
+        assert!(OmmaImage::load("no/such/image.ppm").is_err());
+    }
+
+    #[test]
+    fn drawn_as_half_blocks() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(6, 10)?;
+        let portrait = session.new_window(3, 2).submit(&mut session)?;
+
+        // Each cell stacks two rows and the odd last row sits on base's bg
+        assert!(palettized()?.draw(session.window(portrait)?, 0, 0, &DEFAULT_CELL)? == 6);
+        let window = session.window(portrait)?;
+        let top = window.get_ommacell(0, 0)?;
+        assert!(top.ch == '▀' && top.fg == Some(RED) && top.bg == Some(BLUE));
+        assert!(window.get_ommacell(1, 0)?.ch == '█');
+        let bottom = window.get_ommacell(1, 1)?;
+        assert!(bottom.ch == '▀' && bottom.fg == Some(BLUE) && bottom.bg.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn too_large() {
+        let error = |bytes: &[u8]| OmmaImage::parse(bytes).err().map(|e| e.to_string());
+        let reason = |error: Option<String>| error.is_some_and(|e| e.contains("image too large"));
+
+        // Sizes whose byte counts overflow are rejected before anything is read or allocated
+        assert!(reason(error(b"P6 4611686018427387904 4 255\n")));
+        assert!(reason(error(b"P5 3074457345618258602 3 65535\n")));
+
+        // The largest BMP only overflows where usize is 32 bits, elsewhere its data is missing
+        let mut huge = bmp(1, 1, 32, &[], &[&[0, 0, 0, 0]]);
+        huge[18..22].copy_from_slice(&i32::MAX.to_le_bytes());
+        huge[22..26].copy_from_slice(&i32::MAX.to_le_bytes());
+        assert!(error(&huge).is_some());
+    }
+}
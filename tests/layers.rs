@@ -0,0 +1,128 @@
+use ommatidium::cell::{ATTR_BLINK, DEFAULT_CELL, OmmaCell, PLAYER_CELL};
+use ommatidium::color::{BLUE, GREEN, RED, WHITE};
+use ommatidium::error::OmmaErr;
+use ommatidium::object::{LAYER_EFFECTS, LAYER_ITEMS, ObjectStyle};
+use ommatidium::session::Session;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers_groups_and_styles() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 10)?;
+        let zone = session.new_window(5, 5).submit(&mut session)?;
+        let white = OmmaCell {
+            fg: Some(WHITE),
+            ..PLAYER_CELL
+        };
+        let player = session
+            .new_object()
+            .parent(zone)
+            .offset(1, 1)
+            .cell(&white)
+            .group("party")
+            .submit(&mut session)?;
+        // Added later but drawn underneath the player
+        let coin = session
+            .new_object()
+            .parent(zone)
+            .offset(1, 1)
+            .text("$$", &DEFAULT_CELL)
+            .layer(LAYER_ITEMS)
+            .submit(&mut session)?;
+        let spark = session
+            .new_object()
+            .parent(zone)
+            .offset(2, 1)
+            .text("*", &DEFAULT_CELL)
+            .layer(LAYER_EFFECTS)
+            .group("sparks")
+            .submit(&mut session)?;
+
+        session.compose()?;
+        let ch = |session: &Session, x, y| session.cell_at(x, y).map(|cell| cell.ch);
+        assert!(ch(&session, 1, 1) == Some('@'));
+        assert!(ch(&session, 2, 1) == Some('*'));
+        assert!(session.object_at(zone, 1, 1)? == Some(player));
+
+        session.toggle_group("sparks");
+        session.toggle_group("party");
+        session.compose()?;
+        assert!(session.is_group_hidden("party"));
+        assert!(ch(&session, 1, 1) == Some('$'));
+        assert!(ch(&session, 2, 1) == Some('$'));
+        assert!(session.object_at(zone, 1, 1)? == Some(coin));
+        assert!(!session.object(spark)?.is_visible());
+
+        // Objects joining a hidden group are hidden straight away, not on the next compose
+        let late = session
+            .new_object()
+            .parent(zone)
+            .offset(3, 3)
+            .text("*", &DEFAULT_CELL)
+            .group("sparks")
+            .submit(&mut session)?;
+        assert!(!session.object(late)?.is_visible());
+        session.add_object_group(coin, "sparks")?;
+        assert!(!session.object(coin)?.is_visible());
+        session.remove_object_group(coin, "sparks")?;
+        assert!(session.object(coin)?.is_visible());
+        session.toggle_group("party");
+
+        // Styles change how the player is drawn, not its cell
+        session.flash_object(
+            player,
+            &ObjectStyle::tint(&RED, 1.0),
+            Duration::from_millis(20),
+        )?;
+        session.object(coin)?.set_style(&ObjectStyle::blink());
+        session.compose()?;
+        let fg = session.cell_at(1, 1).and_then(|cell| cell.fg);
+        assert!(fg.map(|fg| (fg.r, fg.g)) == Some((255, 0)));
+        assert!(
+            session
+                .cell_at(2, 1)
+                .is_some_and(|cell| cell.attrs & ATTR_BLINK != 0)
+        );
+        let fg = session.object(player)?.cell().and_then(|cell| cell.fg);
+        assert!(fg.map(|fg| fg.g) == Some(255));
+
+        session.tick()?;
+        session.tick()?;
+        session.compose()?;
+        let fg = session.cell_at(1, 1).and_then(|cell| cell.fg);
+        assert!(fg.map(|fg| fg.g) == Some(255));
+        assert!(session.effect_count() == 0);
+
+        // Flashes draw over the object's style, overlapping ones run until the last ends
+        let recolor = |color| ObjectStyle {
+            fg: Some(color),
+            ..Default::default()
+        };
+        let coin_cell = |session: &Session| session.cell_at(2, 1).cloned().unwrap_or_default();
+        session.flash_object(coin, &recolor(RED), Duration::from_millis(20))?;
+        session.tick()?;
+        session.flash_object(coin, &recolor(GREEN), Duration::from_millis(40))?;
+        session.tick()?;
+        session.tick()?;
+        session.compose()?;
+        assert!(session.effect_count() == 1 && coin_cell(&session).fg == Some(GREEN));
+        session.tick()?;
+        session.compose()?;
+        let restored = coin_cell(&session);
+        assert!(session.effect_count() == 0);
+        assert!(restored.fg.is_none() && restored.attrs & ATTR_BLINK != 0);
+
+        // A style written during a flash shows once it ends, and cancelling a flash ends it
+        let flash = session.flash_object(coin, &recolor(RED), Duration::from_millis(40))?;
+        session.object(coin)?.set_style(&recolor(BLUE));
+        session.compose()?;
+        assert!(coin_cell(&session).fg == Some(RED));
+        session.cancel_effect(flash);
+        session.compose()?;
+        assert!(session.object(coin)?.flash().is_none() && coin_cell(&session).fg == Some(BLUE));
+        Ok(())
+    }
+}
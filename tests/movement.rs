@@ -0,0 +1,60 @@
+use ommatidium::cell::{DEFAULT_CELL, FLOOR_CELL, PLAYER_CELL, WALL_CELL};
+use ommatidium::error::OmmaErr;
+use ommatidium::object::MoveBlocker;
+use ommatidium::session::Session;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocked_moves() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 10)?;
+        let zone = session
+            .new_window(6, 4)
+            .fill(&FLOOR_CELL)
+            .submit(&mut session)?;
+        session.window(zone)?.set_ommacell(3, 0, &WALL_CELL)?;
+        assert!(session.window(zone)?.solidify(&WALL_CELL) == 1);
+
+        let player = session
+            .new_object()
+            .parent(zone)
+            .offset(2, 0)
+            .cell(&PLAYER_CELL)
+            .submit(&mut session)?;
+        let goblin = session
+            .new_object()
+            .parent(zone)
+            .offset(2, 2)
+            .text("gg", &DEFAULT_CELL)
+            .submit(&mut session)?;
+        let coin = session
+            .new_object()
+            .parent(zone)
+            .offset(1, 1)
+            .cell(&DEFAULT_CELL)
+            .passable()
+            .submit(&mut session)?;
+
+        let mut handle = session.object_handle(player)?;
+        assert!(handle.try_move(0, -1)? == Some(MoveBlocker::Edge));
+        assert!(handle.try_move(1, 0)? == Some(MoveBlocker::Wall(3, 0)));
+        assert!(handle.move_to(3, 2).is_err());
+        assert!(handle.offset_x() == 2);
+
+        // Passable objects are walked over
+        handle.move_by(-1, 1)?;
+        assert!(handle.offset_y() == 1);
+
+        // The whole extent of a string object collides
+        let blocked = session.try_move_object(goblin, -1, -1)?;
+        assert!(blocked == Some(MoveBlocker::Object(player)));
+        let blocked = session.try_move_object(goblin, 3, 0)?;
+        assert!(blocked == Some(MoveBlocker::Edge));
+        session.move_object_by(goblin, 2, 0)?;
+        assert!(session.object_at(zone, 5, 2)? == Some(goblin));
+        assert!(session.object_at(zone, 1, 1)? == Some(coin));
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use ommatidium::cell::{DEFAULT_CELL, OmmaCell};
+use ommatidium::color::{BLACK, GREEN};
+use ommatidium::error::OmmaErr;
+use ommatidium::pixel::{OmmaPixels, PixelMode};
+use ommatidium::session::Session;
+use ommatidium::theme::OmmaRole;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// base is the cell pixels are packed over, unlit pixels show its black bg
+    fn base() -> OmmaCell {
+        OmmaCell {
+            bg: Some(BLACK),
+            ..DEFAULT_CELL
+        }
+    }
+
+    #[test]
+    fn sparkline_packs_into_braille() {
+        // Two samples per cell, four dots high
+        let samples = [0, 1, 2, 3, 7, 5, 4, 6];
+        let mut sparkline = OmmaPixels::new(PixelMode::Braille, 4, 2);
+        for (x, sample) in samples.iter().enumerate() {
+            let x = x as isize;
+            sparkline.line(x, 7, x, 7 - sample, &GREEN);
+        }
+        let region = sparkline.region(&base());
+        let bottom = region.get(0, 1).cloned().unwrap_or_default();
+        assert!(bottom.ch == '⣠' && bottom.fg == Some(GREEN) && bottom.bg == Some(BLACK));
+        assert!(region.get(0, 0).map(|cell| cell.ch) == Some(' '));
+        assert!(region.get(2, 0).map(|cell| cell.ch) == Some('⣧'));
+    }
+
+    #[test]
+    fn lit_cells() {
+        let mut bar = OmmaPixels::new(PixelMode::HalfBlock, 2, 1);
+        bar.plot(1, 1, &GREEN);
+        assert!(!bar.is_lit(0, 0) && bar.is_lit(1, 0));
+        bar.clear();
+        assert!(!bar.is_lit(1, 0));
+    }
+
+    #[test]
+    fn lit_cells_drop_base_tail_and_role() {
+        let themed = OmmaCell {
+            ch: 'e',
+            tail: Some(Rc::from("\u{301}")),
+            role: Some(OmmaRole::Text),
+            ..base()
+        };
+        let mut bar = OmmaPixels::new(PixelMode::HalfBlock, 2, 1);
+        bar.plot(1, 1, &GREEN);
+        let unlit = bar.cell(0, 0, &themed);
+        assert!(unlit.ch == ' ' && unlit.tail.is_none() && unlit.role == Some(OmmaRole::Text));
+        let lit = bar.cell(1, 0, &themed);
+        assert!(lit.ch == '▄' && lit.tail.is_none() && lit.role.is_none());
+        assert!(lit.fg == Some(GREEN) && lit.bg == Some(BLACK));
+    }
+
+    #[test]
+    fn drawn_into_windows() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(6, 10)?;
+        let chart = session.new_window(6, 2).submit(&mut session)?;
+
+        // Half blocks clip to the window, everything past its edge is dropped
+        let mut bar = OmmaPixels::new(PixelMode::HalfBlock, 8, 1);
+        bar.rect_filled(0, 1, 8, 1, &GREEN);
+        assert!(bar.draw(session.window(chart)?, 2, 1, &base())? == 4);
+        let block = session.window(chart)?.get_ommacell(5, 1)?;
+        assert!(block.ch == '▄' && block.fg == Some(GREEN) && block.bg == Some(BLACK));
+        assert!(bar.draw(session.window(chart)?, 6, 0, &base()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use ommatidium::cell::{DEFAULT_CELL, OmmaCell};
+use ommatidium::color::{BLACK, GREEN};
+use ommatidium::error::OmmaErr;
+use ommatidium::pixel::PixelMode;
+use ommatidium::session::Session;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_canvas_window() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(6, 10)?;
+        let dotted = OmmaCell {
+            ch: '.',
+            bg: Some(BLACK),
+            ..DEFAULT_CELL
+        };
+        let canvas = session
+            .new_window(2, 1)
+            .fill(&dotted)
+            .pixels(PixelMode::HalfBlock)
+            .submit(&mut session)?;
+        if let Some(pixels) = session.window(canvas)?.pixels_mut() {
+            pixels.plot(0, 0, &GREEN);
+        }
+
+        // Lit cells are packed at blit, unlit ones and the buffer itself are left alone
+        session.compose()?;
+        let packed = session.cell_at(0, 0).cloned().unwrap_or_default();
+        assert!(packed.ch == '▀' && packed.fg == Some(GREEN) && packed.bg == Some(BLACK));
+        assert!(session.cell_at(1, 0).map(|cell| cell.ch) == Some('.'));
+        assert!(session.window(canvas)?.get_ommacell(0, 0)? == dotted);
+
+        session.window(canvas)?.remove_pixels();
+        session.compose()?;
+        assert!(session.cell_at(0, 0).map(|cell| cell.ch) == Some('.'));
+        Ok(())
+    }
+}
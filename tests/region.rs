@@ -0,0 +1,54 @@
+use ommatidium::canvas::OmmaRegion;
+use ommatidium::cell::{FLOOR_CELL, OmmaCell, WALL_CELL};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_bounds() {
+        let mut region = OmmaRegion::new(2, 1, &FLOOR_CELL);
+        assert!(region.set(1, 0, &WALL_CELL).is_ok());
+        assert!(region.get(1, 0) == Some(&WALL_CELL));
+        assert!(region.set(2, 0, &WALL_CELL).is_err() && region.get(0, 1).is_none());
+    }
+
+    #[test]
+    fn regions() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 20)?;
+        let map = session
+            .new_window(12, 8)
+            .fill(&FLOOR_CELL)
+            .submit(&mut session)?;
+        let water = OmmaCell {
+            ch: '~',
+            ..FLOOR_CELL
+        };
+        let window = session.window(map)?;
+        window.plot(6, 4, &water);
+        window.plot(5, 3, &WALL_CELL);
+
+        // Copies are clipped to the buffer
+        let stamp = window.copy_region(5, 3, 3, 3);
+        assert!(stamp.width() == 3 && stamp.height() == 3);
+        assert!(window.copy_region(10, 6, 5, 5).width() == 2);
+
+        // Pasting skips cells matching the key, so the floor around the water stays transparent
+        let minimap = session
+            .new_window(4, 4)
+            .fill(&WALL_CELL)
+            .submit(&mut session)?;
+        let minimap_window = session.window(minimap)?;
+        assert!(minimap_window.paste_region(2, 2, &stamp, Some(&FLOOR_CELL)) == 2);
+        assert!(minimap_window.get_ommacell(2, 2)?.ch == '#');
+        assert!(minimap_window.get_ommacell(3, 3)?.ch == '~');
+        assert!(minimap_window.get_ommacell(3, 2)?.ch == '#');
+
+        // Whole windows blit into each other the same way
+        assert!(session.blit_window(minimap, map, 0, 0, Some(&WALL_CELL))? == 1);
+        assert!(session.window(map)?.get_ommacell(3, 3)?.ch == '~');
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use ommatidium::effect::EffectTarget;
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retired_targets() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 10)?;
+        let gone = session.new_window(2, 2).submit(&mut session)?;
+        let kept = session.new_window(2, 2).submit(&mut session)?;
+        for window in [gone, kept] {
+            session
+                .new_effect(EffectTarget::Window(window))
+                .lifetime(if window == gone {
+                    Duration::ZERO
+                } else {
+                    Duration::from_secs(1)
+                })
+                .submit(&mut session)?;
+        }
+
+        // The first effect's window was retired by hand before its effect expires
+        session.retire_window(gone)?;
+        session.retire_window(gone)?;
+        session.tick()?;
+        assert!(session.effect_count() == 1);
+        assert!(session.window(0)?.children().contains(&kept));
+        Ok(())
+    }
+}
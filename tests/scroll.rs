@@ -0,0 +1,56 @@
+use ommatidium::cell::{FLOOR_CELL, OmmaCell, PLAYER_CELL};
+use ommatidium::color::{BLACK, OmmaColor, WHITE};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+use ommatidium::shadow::OmmaShadow;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrolled_children() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(8, 12)?;
+        let tile = OmmaCell {
+            fg: Some(WHITE),
+            bg: Some(WHITE),
+            ..FLOOR_CELL
+        };
+        let map = session
+            .new_window(10, 6)
+            .view(6, 4)
+            .offset(2, 1)
+            .fill(&tile)
+            .submit(&mut session)?;
+        let child = OmmaCell::new('C', None, Some(BLACK), 0);
+        session
+            .new_window(2, 1)
+            .parent(map)
+            .offset(3, 2)
+            .fill(&child)
+            .shadow(&OmmaShadow::new(1, 1))
+            .submit(&mut session)?;
+        session
+            .new_object()
+            .parent(map)
+            .offset(2, 2)
+            .cell(&PLAYER_CELL)
+            .submit(&mut session)?;
+
+        // Child windows, their shadows and objects all move with the scrolled content
+        session.window(map)?.set_scroll(2, 1);
+        session.compose()?;
+        let ch = |session: &Session, x, y| session.cell_at(x, y).map(|cell| cell.ch);
+        assert!(ch(&session, 2, 2) == Some('@'));
+        assert!(ch(&session, 3, 2) == Some('C') && ch(&session, 4, 2) == Some('C'));
+        let grey = Some(OmmaColor::new(127, 127, 127));
+        assert!(session.cell_at(5, 3).map(|cell| cell.bg) == Some(grey));
+        assert!(session.cell_at(6, 3) == Some(&tile));
+
+        // Scrolled back the child returns to its own offset
+        session.window(map)?.set_scroll(0, 0);
+        session.compose()?;
+        assert!(ch(&session, 5, 3) == Some('C') && ch(&session, 3, 2) == Some('.'));
+        Ok(())
+    }
+}
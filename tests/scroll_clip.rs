@@ -0,0 +1,41 @@
+use ommatidium::border::BOX_HOLLOW_BORDER;
+use ommatidium::cell::{FLOOR_CELL, OmmaCell};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrolled_children_clip() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(8, 12)?;
+        let map = session
+            .new_window(8, 8)
+            .view(6, 5)
+            .offset(1, 1)
+            .border(&BOX_HOLLOW_BORDER)
+            .fill(&FLOOR_CELL)
+            .submit(&mut session)?;
+        session
+            .new_window(3, 2)
+            .parent(map)
+            .offset(2, 0)
+            .fill(&OmmaCell::new('C', None, None, 0))
+            .submit(&mut session)?;
+        session.compose()?;
+        let top = session.cell_at(4, 1).cloned();
+        let right = session.cell_at(6, 2).cloned();
+
+        // Scrolled up a row, the child is cut at the top border instead of skipped, and its
+        // right column stops at the right border
+        session.window(map)?.set_scroll(0, 1);
+        session.compose()?;
+        let ch = |session: &Session, x, y| session.cell_at(x, y).map(|cell| cell.ch);
+        assert!(ch(&session, 4, 2) == Some('C') && ch(&session, 5, 2) == Some('C'));
+        assert!(session.cell_at(4, 1).cloned() == top);
+        assert!(session.cell_at(6, 2).cloned() == right);
+        assert!(ch(&session, 4, 3) == Some('.'));
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use ommatidium::cell::DEFAULT_CELL;
+use ommatidium::color::{BLUE, GREEN, RED};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+use ommatidium::shader::RainbowShader;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rainbow_frames() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 10)?;
+        let id = session.new_window(5, 1).offset(2, 3).submit(&mut session)?;
+        session.window(id)?.string_shaded(
+            0,
+            0,
+            &DEFAULT_CELL,
+            "Hey!".to_string(),
+            Rc::new(RainbowShader::new(&[RED, GREEN, BLUE], 1)),
+        )?;
+
+        session.compose()?;
+        let fg = session.cell_at(3, 3).and_then(|cell| cell.fg);
+        assert!(fg.map(|fg| fg.g) == Some(255));
+
+        // One frame later the rainbow has moved a cell along
+        session.tick()?;
+        session.compose()?;
+        let fg = session.cell_at(3, 3).and_then(|cell| cell.fg);
+        assert!(fg.map(|fg| fg.b) == Some(255));
+        assert!(session.cell_at(3, 3).map(|cell| cell.ch) == Some('e'));
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use ommatidium::cell::{FLOOR_CELL, OmmaCell, WALL_CELL};
+use ommatidium::color::{BLACK, OmmaColor, RED, WHITE};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+use ommatidium::shadow::OmmaShadow;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// tile is a white floor tile, so shading shows up in both colors
+    fn tile() -> OmmaCell {
+        OmmaCell {
+            fg: Some(WHITE),
+            bg: Some(WHITE),
+            ..FLOOR_CELL
+        }
+    }
+
+    #[test]
+    fn shaded_cells_keep_their_glyph() {
+        let shaded = OmmaShadow::default().cell().over(&tile());
+        let grey = Some(OmmaColor::new(127, 127, 127));
+        assert!(shaded.ch == '.' && shaded.bg == grey && shaded.fg == grey);
+    }
+
+    #[test]
+    fn tint_and_glyph_replace_the_defaults() {
+        let tinted = OmmaShadow::new(1, 1).tint(&RED, 1.0).glyph('░');
+        let shaded = tinted.cell().over(&tile());
+        assert!(shaded.ch == '░' && shaded.bg == Some(RED) && shaded.fg == Some(RED));
+        assert!(OmmaShadow::new(1, 1).tint(&RED, 2.0).amount() == 1.0);
+    }
+
+    #[test]
+    fn shadows_fall_beside_the_window() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(6, 12)?;
+        session
+            .new_window(12, 6)
+            .fill(&tile())
+            .submit(&mut session)?;
+        session
+            .new_window(4, 2)
+            .offset(2, 1)
+            .fill(&OmmaCell::new('D', None, Some(BLACK), 0))
+            .shadow(&OmmaShadow::default())
+            .submit(&mut session)?;
+
+        // The default shadow falls two columns right and one row down, under the window itself
+        session.compose()?;
+        let grey = Some(OmmaColor::new(127, 127, 127));
+        let bg = |session: &Session, x, y| session.cell_at(x, y).and_then(|cell| cell.bg);
+        assert!(bg(&session, 6, 2) == grey && bg(&session, 7, 2) == grey);
+        assert!(bg(&session, 4, 3) == grey);
+        assert!(bg(&session, 3, 3) == Some(WHITE) && bg(&session, 6, 1) == Some(WHITE));
+        assert!(session.cell_at(5, 2).map(|cell| cell.ch) == Some('D'));
+
+        // A window submitted later draws over the shadow
+        session
+            .new_window(1, 1)
+            .offset(7, 2)
+            .fill(&WALL_CELL)
+            .submit(&mut session)?;
+        session.compose()?;
+        assert!(session.cell_at(7, 2).map(|cell| cell.ch) == Some('#'));
+        Ok(())
+    }
+}
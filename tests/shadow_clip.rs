@@ -0,0 +1,62 @@
+use ommatidium::cell::{FLOOR_CELL, OmmaCell};
+use ommatidium::color::{BLACK, RED, WHITE};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+use ommatidium::shadow::OmmaShadow;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shadows_clip() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(6, 12)?;
+        let tile = OmmaCell {
+            fg: Some(WHITE),
+            bg: Some(WHITE),
+            ..FLOOR_CELL
+        };
+        let map = session.new_window(12, 6).fill(&tile).submit(&mut session)?;
+        let dialog = OmmaCell::new('D', None, Some(BLACK), 0);
+        let tinted = OmmaShadow::new(1, 1).tint(&RED, 1.0).glyph('░');
+
+        // Clipped by the bottom of the screen, the shadow's right column still shows
+        session
+            .new_window(3, 2)
+            .parent(map)
+            .offset(0, 4)
+            .fill(&dialog)
+            .shadow(&tinted)
+            .submit(&mut session)?;
+        // In the bottom right corner the whole shadow is off screen
+        session
+            .new_window(2, 2)
+            .offset(10, 4)
+            .fill(&dialog)
+            .shadow(&tinted)
+            .submit(&mut session)?;
+        // Inside a small box the whole shadow falls outside the parent's content
+        let boxed = session
+            .new_window(3, 2)
+            .offset(8, 0)
+            .fill(&tile)
+            .submit(&mut session)?;
+        session
+            .new_window(2, 2)
+            .parent(boxed)
+            .offset(1, 0)
+            .fill(&dialog)
+            .shadow(&tinted)
+            .submit(&mut session)?;
+
+        session.compose()?;
+        let red = session.cell_at(3, 5).cloned().unwrap_or_default();
+        assert!(red.ch == '░' && red.bg == Some(RED));
+        assert!(session.cell_at(11, 5).map(|cell| cell.ch) == Some('D'));
+        assert!(session.cell_at(9, 5) == Some(&tile));
+        for (x, y) in [(11, 1), (10, 2), (11, 2)] {
+            assert!(session.cell_at(x, y) == Some(&tile));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use ommatidium::cell::{DEFAULT_CELL, FLOOR_CELL};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+use ommatidium::sprite::OmmaSprite;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sprite_objects() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 10)?;
+        let map = session
+            .new_window(8, 8)
+            .view(4, 4)
+            .scroll(1, 0)
+            .fill(&FLOOR_CELL)
+            .submit(&mut session)?;
+
+        // A two by two monster with a transparent top right corner
+        let ogre = OmmaSprite::from_rows(&["O.", "OO"], &DEFAULT_CELL, Some('.'));
+        let monster = session
+            .new_object()
+            .parent(map)
+            .offset(0, 1)
+            .sprite(&ogre)
+            .submit(&mut session)?;
+        let label = session
+            .new_object()
+            .parent(map)
+            .offset(2, 3)
+            .text("ogre", &DEFAULT_CELL)
+            .submit(&mut session)?;
+
+        // The monster's left column is scrolled out and the label is clipped by the view
+        session.compose()?;
+        let ch = |session: &Session, x, y| session.cell_at(x, y).map(|cell| cell.ch);
+        assert!(ch(&session, 0, 1) == Some(FLOOR_CELL.ch));
+        assert!(ch(&session, 0, 2) == Some('O'));
+        assert!(ch(&session, 1, 3) == Some('o'));
+        assert!(ch(&session, 3, 3) == Some('r'));
+        assert!(ch(&session, 4, 3) != Some('e'));
+
+        assert!(session.object(monster)?.contains(1, 1));
+        assert!(session.object_at(map, 1, 1)?.is_none());
+        assert!(session.object_at(map, 1, 2)? == Some(monster));
+        assert!(session.object_at(map, 5, 3)? == Some(label));
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use ommatidium::border::BOX_HOLLOW_BORDER;
+use ommatidium::error::OmmaErr;
+use ommatidium::key::KEY_TAB;
+use ommatidium::session::Session;
+use ommatidium::tabs::Tabs;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switch_pages() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(50, 50)?;
+        let container = session
+            .new_window(30, 10)
+            .offset(2, 1)
+            .border(&BOX_HOLLOW_BORDER)
+            .tabs(&Tabs::new())
+            .submit(&mut session)?;
+        let stats = session
+            .new_window(28, 8)
+            .parent(container)
+            .page("Stats")
+            .submit(&mut session)?;
+        let skills = session
+            .new_window(28, 8)
+            .parent(container)
+            .page("Skills")
+            .submit(&mut session)?;
+        let equipment = session
+            .new_window(28, 8)
+            .parent(container)
+            .page("Equipment")
+            .submit(&mut session)?;
+        assert!(!session.window(stats)?.is_hidden());
+        assert!(session.window(skills)?.is_hidden());
+        let orphan = session.new_window(1, 1).page("Orphan");
+        assert!(orphan.submit(&mut session).is_err());
+
+        assert!(session.tab_key(container, KEY_TAB)? == Some(1));
+        assert!(session.window(stats)?.is_hidden());
+        assert!(!session.window(skills)?.is_hidden());
+        assert!(session.tab_key(container, '4')?.is_none());
+
+        // " Stats " spans screen columns 3 through 9 of the top border right of its corner
+        session.compose()?;
+        assert!(session.cell_at(4, 1).map(|cell| cell.ch) == Some('S'));
+        assert!(session.click(5, 1)? == Some((container, 0)));
+        assert!(session.click(10, 1)?.is_none() && session.click(5, 2)?.is_none());
+        let tabs = session.window(container)?.tabs().cloned();
+        assert!(tabs.and_then(|tabs| tabs.active_page()) == Some(stats));
+
+        // Retiring the active page leaves its container and shows the page before it
+        session.activate_tab(container, 2)?;
+        session.retire_window(equipment)?;
+        let tabs = session.window(container)?.tabs().cloned();
+        assert!(tabs.is_some_and(|tabs| tabs.pages() == vec![stats, skills]));
+        assert!(!session.window(skills)?.is_hidden());
+        session.render()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use ommatidium::cell::{ATTR_BOLD, ATTR_REVERSE, DEFAULT_CELL, OmmaCell};
+use ommatidium::color::{BLACK, GREEN, RED};
+use ommatidium::error::OmmaErr;
+use ommatidium::gauge::Gauge;
+use ommatidium::label::OmmaLabel;
+use ommatidium::menu::Menu;
+use ommatidium::session::Session;
+use ommatidium::text::{StyledText, TextStyle};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn styled_text() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(20, 10)?;
+        let dialog = session.new_window(12, 3).submit(&mut session)?;
+        let base = OmmaCell {
+            bg: Some(BLACK),
+            ..DEFAULT_CELL
+        };
+
+        // Spans keep the base cell's colors they do not set themselves
+        let written =
+            session
+                .window(dialog)?
+                .markup(0, 0, &base, "[green]Yes![/] [bold red]No![/]")?;
+        assert!(written == 8);
+        let window = session.window(dialog)?;
+        let yes = window.get_ommacell(0, 0)?;
+        assert!(yes.ch == 'Y' && yes.fg == Some(GREEN) && yes.bg == Some(BLACK));
+        let gap = window.get_ommacell(4, 0)?;
+        assert!(gap.fg.is_none() && gap.attrs == 0);
+        let no = window.get_ommacell(5, 0)?;
+        assert!(no.ch == 'N' && no.fg == Some(RED) && no.attrs == ATTR_BOLD);
+        assert!(window.markup(0, 1, &base, "[red]unclosed").is_ok());
+        assert!(window.markup(0, 1, &base, "[sparkly]nope[/]").is_err());
+
+        // Built span by span and drawn by widgets
+        let text = StyledText::new()
+            .span("HP ", &TextStyle::new())
+            .span("12", &TextStyle::new().fg(&RED));
+        let label = OmmaLabel::new(text.clone());
+        assert!(label.text() == "HP 12");
+        assert!(label.cells(7)[4].fg == Some(RED));
+
+        let mut menu = Menu::new()
+            .item(StyledText::parse("[green]Yes[/]")?, true)
+            .item("No", false);
+        let list = session.new_window(5, 2).offset(0, 4).submit(&mut session)?;
+        menu.draw(session.window(list)?)?;
+        let selected = session.window(list)?.get_ommacell(0, 0)?;
+        assert!(selected.fg == Some(GREEN) && selected.attrs == ATTR_REVERSE);
+
+        // A gauge label span keeps its own background even when it matches the label cell's
+        let text = StyledText::new()
+            .span("HP", &TextStyle::new())
+            .span("!", &TextStyle::new().bg(&BLACK));
+        let bar = session.new_window(5, 1).offset(0, 7).submit(&mut session)?;
+        let fill = OmmaCell {
+            fg: Some(GREEN),
+            ..DEFAULT_CELL
+        };
+        Gauge::new(1.0)
+            .fill(&fill)
+            .label(text, &base)
+            .draw(session.window(bar)?)?;
+        let window = session.window(bar)?;
+        let (h, bang) = (window.get_ommacell(1, 0)?, window.get_ommacell(3, 0)?);
+        assert!(h.ch == 'H' && h.bg == Some(GREEN));
+        assert!(bang.ch == '!' && bang.bg == Some(BLACK));
+        Ok(())
+    }
+}
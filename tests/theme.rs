@@ -0,0 +1,114 @@
+use ommatidium::border::BOX_HOLLOW_BORDER;
+use ommatidium::cell::{ATTR_BOLD, ATTR_REVERSE, OmmaCell};
+use ommatidium::color::{BLACK, BLUE, GREEN, OmmaColor, RED, WHITE};
+use ommatidium::error::OmmaErr;
+use ommatidium::menu::Menu;
+use ommatidium::session::Session;
+use ommatidium::theme::{OmmaRole, OmmaTheme};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn themed_windows() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(10, 20)?;
+        let background = OmmaCell::themed(OmmaRole::Background);
+        let outer = session
+            .new_window(12, 8)
+            .fill(&background)
+            .border(&BOX_HOLLOW_BORDER)
+            .submit(&mut session)?;
+        let inner = session
+            .new_window(4, 2)
+            .offset(1, 5)
+            .parent(outer)
+            .fill(&background)
+            .submit(&mut session)?;
+        session
+            .window(outer)?
+            .markup(0, 0, &background, "[title]Hi[/]")?;
+
+        // Without a theme roles draw as written
+        session.compose()?;
+        assert!(
+            session
+                .cell_at(1, 1)
+                .cloned()
+                .unwrap_or_default()
+                .bg
+                .is_none()
+        );
+
+        let dusk = OmmaTheme::parse("background = on black\nborder = blue\ntitle = bold red")?;
+        session.set_theme(&dusk);
+        session.compose()?;
+        assert!(session.cell_at(0, 0).cloned().unwrap_or_default().fg == Some(BLUE));
+        let title = session.cell_at(1, 1).cloned().unwrap_or_default();
+        assert!(title.ch == 'H' && title.fg == Some(RED) && title.attrs == ATTR_BOLD);
+        assert!(title.bg == Some(BLACK));
+
+        // Attributes from markup survive a role that sets none
+        session.set_theme(&OmmaTheme::parse("text = #c5c8c6")?);
+        session
+            .window(outer)?
+            .markup(0, 2, &background, "[text bold]Ok[/]")?;
+        session.compose()?;
+        let text = session.cell_at(1, 3).cloned().unwrap_or_default();
+        assert!(text.ch == 'O' && text.fg == Some(OmmaColor::new(197, 200, 198)));
+        assert!(text.attrs == ATTR_BOLD);
+        session.set_theme(&dusk);
+
+        // A window theme overrides its subtree and falls through for roles it leaves out
+        session.window(inner)?.set_theme(&OmmaTheme::new().role(
+            OmmaRole::Background,
+            &OmmaCell::new(' ', None, Some(GREEN), 0),
+        ));
+        session.compose()?;
+        assert!(session.cell_at(2, 6).cloned().unwrap_or_default().bg == Some(GREEN));
+        assert!(session.cell_at(1, 2).cloned().unwrap_or_default().bg == Some(BLACK));
+
+        // Switching themes restyles everything on the next compose
+        session.set_theme(&OmmaTheme::parse("background = on white\nborder = green")?);
+        session.compose()?;
+        assert!(session.cell_at(0, 0).cloned().unwrap_or_default().fg == Some(GREEN));
+        assert!(session.cell_at(1, 2).cloned().unwrap_or_default().bg == Some(WHITE));
+        assert!(session.cell_at(2, 6).cloned().unwrap_or_default().bg == Some(GREEN));
+        let title = session.cell_at(1, 1).cloned().unwrap_or_default();
+        assert!(title.fg.is_none() && title.bg == Some(WHITE));
+
+        // Widgets draw in their default roles
+        session.set_theme(&OmmaTheme::parse("selection = reverse blue")?);
+        let mut menu = Menu::new().item("Yes", true).item("No", true);
+        menu.draw(session.window(inner)?)?;
+        session.compose()?;
+        let selected = session.cell_at(2, 6).cloned().unwrap_or_default();
+        assert!(selected.ch == 'Y' && selected.fg == Some(BLUE));
+        assert!(selected.attrs == ATTR_REVERSE);
+        Ok(())
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let err = OmmaTheme::parse("text = red\nheading = bold")
+            .err()
+            .unwrap();
+        assert!(err.body().contains("line 2"));
+    }
+
+    #[test]
+    fn missing_theme_file() {
+        assert!(OmmaTheme::load("no/such/theme").is_err());
+    }
+
+    #[test]
+    fn parsed_roles() -> Result<(), OmmaErr> {
+        let theme = OmmaTheme::parse("background = on black\ntitle = bold red")?;
+        let title = theme.get(OmmaRole::Title).cloned().unwrap_or_default();
+        assert!(title.fg == Some(RED) && title.bg.is_none() && title.attrs == ATTR_BOLD);
+        let background = theme.get(OmmaRole::Background).cloned().unwrap_or_default();
+        assert!(background.bg == Some(BLACK));
+        assert!(theme.get(OmmaRole::Border).is_none());
+        Ok(())
+    }
+}
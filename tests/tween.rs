@@ -0,0 +1,69 @@
+use ommatidium::cell::DEFAULT_CELL;
+use ommatidium::color::{BLACK, RED};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+use ommatidium::tween::{Animation, Easing, Tween};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slide_and_fade() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(20, 20)?;
+        let menu = session.new_window(4, 4).offset(0, 2).submit(&mut session)?;
+        let map = session.new_window(10, 10).view(5, 5).submit(&mut session)?;
+        let player = session
+            .new_object()
+            .parent(map)
+            .cell(&DEFAULT_CELL)
+            .submit(&mut session)?;
+
+        // Slide the menu in a cell per frame while the player turns red, then pan the map
+        let done = Rc::new(Cell::new(false));
+        let flag = done.clone();
+        let frame = Duration::from_nanos(1_000_000_000 / 60);
+        Animation::new()
+            .then(Tween::window_offset(menu, 6, 2, frame * 6))
+            .with(Tween::object_fg(player, &RED, frame * 2))
+            .then(Tween::window_scroll(map, 5, 5, frame * 2).easing(Easing::CubicOut))
+            .on_complete(move |_| {
+                flag.set(true);
+                Ok(())
+            })
+            .submit(&mut session)?;
+
+        session.tick()?;
+        assert!(session.window(menu)?.offset_x() == 1);
+        session.tick()?;
+        // Colors are tweened through the object's style, its cell stays as it was
+        let fg = session.object(player)?.style().and_then(|style| style.fg);
+        assert!(fg.map(|fg| (fg.r, fg.g)) == Some((255, 0)));
+        assert!(session.object(player)?.cell() == Some(&DEFAULT_CELL));
+        for _ in 0..4 {
+            session.tick()?;
+        }
+        assert!(session.window(menu)?.offset_x() == 6);
+        assert!(session.window(map)?.scroll_x() == 0);
+
+        session.tick()?;
+        session.tick()?;
+        assert!(session.window(map)?.scroll_y() == 5);
+        assert!(done.get());
+        assert!(session.effect_count() == 0);
+
+        // A cancelled tween stays where it was
+        let slide = Tween::object_offset(player, 9, 0, frame * 9).submit(&mut session)?;
+        let fade = Tween::object_bg(player, &BLACK, frame).submit(&mut session)?;
+        session.tick()?;
+        session.cancel_effect(slide);
+        assert!(!session.has_effect(slide));
+        assert!(!session.has_effect(fade));
+        session.tick()?;
+        assert!(session.object(player)?.offset_x() == 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use ommatidium::cell::{CONTINUATION, DEFAULT_CELL, FLOOR_CELL};
+use ommatidium::error::OmmaErr;
+use ommatidium::session::Session;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_text() -> Result<(), OmmaErr> {
+        let mut session = Session::new_headless(5, 10)?;
+        let shop = session
+            .new_window(8, 1)
+            .fill(&FLOOR_CELL)
+            .submit(&mut session)?;
+        let written =
+            session
+                .window(shop)?
+                .string_raw(0, 0, &DEFAULT_CELL, "回復薬x".to_string())?;
+        assert!(written == 7);
+        assert!(
+            session
+                .window(shop)?
+                .string_raw(1, 0, &DEFAULT_CELL, "ポーション".to_string())
+                .is_err()
+        );
+        let written =
+            session
+                .window(shop)?
+                .string_raw(0, 0, &DEFAULT_CELL, "Cafe\u{301}".to_string())?;
+        assert!(written == 4);
+        session
+            .window(shop)?
+            .string_raw(4, 0, &DEFAULT_CELL, "薬草".to_string())?;
+
+        // A window covering the right half of 草 leaves its left half blank
+        let cover = session
+            .new_window(1, 1)
+            .offset(7, 0)
+            .fill(&FLOOR_CELL)
+            .submit(&mut session)?;
+        session.compose()?;
+        let ch = |session: &Session, x| session.cell_at(x, 0).map(|cell| cell.ch);
+        assert!(session.cell_at(3, 0).map(|cell| cell.glyph()) == Some("e\u{301}".to_string()));
+        assert!(ch(&session, 4) == Some('薬'));
+        assert!(ch(&session, 5) == Some(CONTINUATION));
+        assert!(ch(&session, 6) == Some(' '));
+        assert!(ch(&session, 7) == Some('.'));
+
+        session.window(cover)?.set_hidden();
+        session.compose()?;
+        assert!(ch(&session, 6) == Some('草'));
+        assert!(ch(&session, 7) == Some(CONTINUATION));
+        Ok(())
+    }
+}